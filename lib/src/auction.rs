@@ -0,0 +1,76 @@
+use ethers::{
+    providers::Middleware,
+    types::{H256, I256, U256},
+};
+
+use crate::{
+    ddso::vat::{Ilk, Vat, RU256},
+    math::{rpow, units},
+};
+
+// vat.safe spots
+pub const SUNK: u64 = 0;
+pub const IFFY: u64 = 1;
+pub const SAFE: u64 = 2;
+
+// Per-ilk auction curve. The keeper pays tot * (deal^pep * pop + pup), capped at the bill.
+#[derive(Debug, Clone, Default)]
+pub struct AuctionParams {
+    pub pep: U256,
+    pub pop: U256,
+    pub pup: I256,
+}
+
+impl AuctionParams {
+    pub async fn fetch<T: Middleware + Clone>(vat: &Vat<T>, ilk: &str) -> Self {
+        Self {
+            pep: vat.geth::<RU256>(ilk, "pep", Vec::new()).await.into(),
+            pop: vat.geth::<RU256>(ilk, "pop", Vec::new()).await.into(),
+            pup: I256::from_raw(U256::from_big_endian(
+                vat.geth::<H256>(ilk, "pup", Vec::new()).await.as_bytes(),
+            )),
+        }
+    }
+}
+
+// All amounts are wads denominated in ref.
+#[derive(Debug, Clone)]
+pub struct BailEstimate {
+    pub bill: U256,
+    pub earn: U256,
+    pub cost: U256,
+    pub value: U256,
+    pub profit: I256,
+}
+
+// Estimates what bailing an urn pays, from the (deal, tot) returned by vat.safe.
+// When earn exceeds the bill the keeper only pays the bill and receives a matching
+// share of the ink, the rest goes back to the urn.
+pub fn estimate_bail(
+    ilk: &Ilk,
+    art: U256,
+    par: U256,
+    deal: U256,
+    tot: U256,
+    params: &AuctionParams,
+) -> BailEstimate {
+    let units = units::new();
+    let bill = ilk.chop * art / units.RAY * ilk.rack / units.RAY * par / units.RAY;
+    let mark = tot / units.RAY;
+    let curve = I256::from_raw(rpow(deal, params.pep) * params.pop / units.RAY) + params.pup;
+    let earn = match curve.is_negative() {
+        true => U256::zero(),
+        false => mark * curve.into_raw() / units.RAY,
+    };
+    let (cost, value) = match earn > bill && !earn.is_zero() {
+        true => (bill, mark * bill / earn),
+        false => (earn, mark),
+    };
+    BailEstimate {
+        bill,
+        earn,
+        cost,
+        value,
+        profit: I256::from_raw(value) - I256::from_raw(cost),
+    }
+}
@@ -6,6 +6,7 @@ extern crate lazy_static;
 lazy_static::lazy_static ! {
     pub static ref NEW_PALM_2_SIG: H256 = H256::from(keccak256("NewPalm2(bytes32,bytes32,bytes32,bytes32)"));
     pub static ref NEW_PALM_0_SIG: H256 = H256::from(keccak256("NewPalm0(bytes32,bytes32)"));
+    pub static ref NEW_PALM_1_SIG: H256 = H256::from(keccak256("NewPalm1(bytes32,bytes32,bytes32)"));
 }
 
 #[derive(Clone)]
@@ -62,6 +63,33 @@ impl IntoNewPalm0Vec for Vec<Log> {
     }
 }
 
+#[derive(Clone)]
+pub struct NewPalm1 {
+    pub block_number: U64,
+    pub act: H256,
+    pub ilk: H256,
+    pub val: H256,
+}
+
+impl From<Log> for NewPalm1 {
+    fn from(log: Log) -> Self {
+        let block_number = log.block_number.unwrap();
+        let act = log.topics[1];
+        let ilk = log.topics[2];
+        let val = H256::from_slice(&log.data[..32]);
+        Self { block_number, act, ilk, val}
+    }
+}
+pub trait IntoNewPalm1Vec {
+    fn into_new_palm1_vec(self) -> Vec<NewPalm1>;
+}
+
+impl IntoNewPalm1Vec for Vec<Log> {
+    fn into_new_palm1_vec(self) -> Vec<NewPalm1> {
+        self.into_iter().map(NewPalm1::from).collect()
+    }
+}
+
 #[derive(Clone)]
 pub enum Palms {
     NewPalm0(NewPalm0),
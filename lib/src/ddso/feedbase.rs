@@ -2,9 +2,10 @@ use std::sync::Arc;
 
 use ethers::{
     abi::{Abi, Address},
-    contract::Contract,
+    contract::{Contract, ContractError},
     providers::Middleware,
     types::{H256, U256},
+    utils::keccak256,
 };
 use serde_json::from_str;
 
@@ -31,4 +32,20 @@ impl<T: Middleware + Clone> Feedbase<T> {
             .await
             .unwrap()
     }
+
+    // pull that surfaces reverts, e.g. ErrTTL from adapters reading an expired feed
+    pub async fn try_pull(&self, src: Address, tag: H256) -> Result<(H256, U256), ContractError<T>> {
+        self.contract
+            .method::<(Address, H256), (H256, U256)>("pull", (src, tag))?
+            .call()
+            .await
+    }
+}
+
+// true when `err` is the ErrTTL revert defined in feedbase.json
+pub fn is_err_ttl<T: Middleware>(err: &ContractError<T>) -> bool {
+    match err.as_revert() {
+        Some(data) => data.starts_with(&keccak256("ErrTTL()")[..4]),
+        None => false,
+    }
 }
@@ -1,9 +1,11 @@
 use std::sync::Arc;
 
 use ethers::{
-    abi::{Abi, Address},
-    contract::Contract,
-    providers::Middleware, types:: U256,
+    abi::{self, Abi, Address, ParamType, Token},
+    contract::{Contract, ContractError},
+    providers::Middleware,
+    types::{transaction::eip2718::TypedTransaction, TransactionRequest, TransactionReceipt, U256},
+    utils::keccak256,
 };
 use serde_json::from_str;
 
@@ -29,4 +31,51 @@ impl<T: Middleware + Clone> Gem<T> {
     pub async fn decimals(&self) -> U256 {
         self.contract.method::<(), U256>("decimals", ()).unwrap().call().await.unwrap()
     }
-}
\ No newline at end of file
+
+    pub async fn symbol(&self) -> String {
+        self.string_call("symbol()").await
+    }
+
+    pub async fn name(&self) -> String {
+        self.string_call("name()").await
+    }
+
+    // rico gems return bytes32 symbols and names, most others return a string, so the
+    // return data is decoded by hand rather than through the abi
+    async fn string_call(&self, signature: &str) -> String {
+        let tx: TypedTransaction = TransactionRequest::new()
+            .to(self.address)
+            .data(keccak256(signature)[..4].to_vec())
+            .into();
+        let data = self.contract.client().call(&tx, None).await.unwrap_or_default();
+        match abi::decode(&[ParamType::String], &data) {
+            Ok(tokens) => match tokens.first() {
+                Some(Token::String(value)) => value.clone(),
+                _ => String::new(),
+            },
+            Err(_) => {
+                let value = data.iter().take(32).take_while(|b| **b != 0).copied().collect::<Vec<u8>>();
+                String::from_utf8_lossy(&value).to_string()
+            }
+        }
+    }
+
+    pub async fn allowance(&self, owner: Address, spender: Address) -> U256 {
+        self.contract
+            .method::<(Address, Address), U256>("allowance", (owner, spender))
+            .unwrap()
+            .call()
+            .await
+            .unwrap()
+    }
+
+    pub async fn approve(
+        &self,
+        spender: Address,
+        wad: U256,
+    ) -> Result<Option<TransactionReceipt>, ContractError<T>> {
+        let call = self.contract.method::<(Address, U256), bool>("approve", (spender, wad))?;
+        let pending = call.send().await?;
+        pending.await.map_err(ContractError::from)
+    }
+}
@@ -1,31 +1,50 @@
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, OnceLock},
+};
 
 use ethers::{
     abi::{Abi, Address},
     contract::Contract,
+    contract::{ContractError, Multicall, MULTICALL_ADDRESS},
     providers::Middleware,
-    types::U256,
+    types::{BlockNumber, Bytes, TransactionReceipt, U256},
 };
 use serde_json::from_str;
 
-#[derive(Debug)]
+use crate::univ3::{self, FeeState};
+
+type PositionsTuple = (U256, Address, Address, Address, u32, i32, i32, u128, U256, U256, u128, u128);
+type Slot0Tuple = (U256, i32, u16, u16, u16, u8, bool);
+type TicksTuple = (u128, i128, U256, U256, i64, U256, u32, bool);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeStatus {
+    // price under tick_lower, the position is all token0
+    Below,
+    InRange,
+    // price at or over tick_upper, the position is all token1
+    Above,
+}
+
+#[derive(Debug, Clone)]
 pub struct PositionsData {
     pub nonce: U256,
     pub operator: Address,
     pub token0: Address,
     pub token1: Address,
-    pub fee: U256,
-    pub tick_lower: U256,
-    pub tick_upper: U256,
-    pub liquidity: U256,
+    pub fee: u32,
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    pub liquidity: u128,
     pub fee_growth_inside_0_last_x128: U256,
     pub fee_growth_inside_1_last_x128: U256,
-    pub tokens_owed_0: U256,
-    pub tokens_owed_1: U256,
+    pub tokens_owed_0: u128,
+    pub tokens_owed_1: u128,
 }
 
-impl From<(U256, Address, Address, Address, U256, U256, U256, U256, U256, U256, U256, U256)> for PositionsData {
-    fn from(data: (U256, Address, Address, Address, U256, U256, U256, U256, U256, U256, U256, U256)) -> Self {
+impl From<PositionsTuple> for PositionsData {
+    fn from(data: PositionsTuple) -> Self {
         PositionsData {
             nonce: data.0,
             operator: data.1,
@@ -43,9 +62,59 @@ impl From<(U256, Address, Address, Address, U256, U256, U256, U256, U256, U256,
     }
 }
 
+impl PositionsData {
+    pub fn range_status(&self, tick: i32) -> RangeStatus {
+        if tick < self.tick_lower {
+            RangeStatus::Below
+        } else if tick < self.tick_upper {
+            RangeStatus::InRange
+        } else {
+            RangeStatus::Above
+        }
+    }
+
+    pub fn in_range(&self, tick: i32) -> bool {
+        self.range_status(tick) == RangeStatus::InRange
+    }
+
+    // token1 per token0 at the range bounds, adjusted for the tokens' decimals
+    pub fn price_range(&self, decimals0: u32, decimals1: u32) -> (f64, f64) {
+        (
+            univ3::tick_to_price(self.tick_lower, decimals0, decimals1),
+            univ3::tick_to_price(self.tick_upper, decimals0, decimals1),
+        )
+    }
+
+    // token amounts the position's liquidity is worth at `sqrt_price_x96`, without fees
+    pub fn principal(&self, sqrt_price_x96: U256) -> (U256, U256) {
+        univ3::principal(sqrt_price_x96, self.tick_lower, self.tick_upper, self.liquidity)
+    }
+
+    // everything collectable, tokensOwed plus what was earned since the last checkpoint
+    pub fn fees(&self, state: &FeeState) -> (U256, U256) {
+        let inside = state.fee_growth_inside(self.tick_lower, self.tick_upper);
+        (
+            U256::from(self.tokens_owed_0)
+                + univ3::fees_owed(inside.0, self.fee_growth_inside_0_last_x128, self.liquidity),
+            U256::from(self.tokens_owed_1)
+                + univ3::fees_owed(inside.1, self.fee_growth_inside_1_last_x128, self.liquidity),
+        )
+    }
+
+    // PositionValue.total, principal at `sqrt_price_x96` plus fees
+    pub fn total(&self, sqrt_price_x96: U256, state: &FeeState) -> (U256, U256) {
+        let principal = self.principal(sqrt_price_x96);
+        let fees = self.fees(state);
+        (principal.0 + fees.0, principal.1 + fees.1)
+    }
+}
+
 pub struct NPFM<T: Middleware + Clone> {
     pub address: Address,
     contract: Contract<T>,
+    // the factory and a position's pool and ticks never change, so they're read once
+    factory: OnceLock<Address>,
+    pools: Mutex<HashMap<U256, (Address, i32, i32)>>,
 }
 
 impl<T: Middleware + Clone> NPFM<T> {
@@ -55,16 +124,204 @@ impl<T: Middleware + Clone> NPFM<T> {
 
         let contract = Contract::new(address, abi, Arc::clone(provider));
 
-        Self { address, contract }
+        Self {
+            address,
+            contract,
+            factory: OnceLock::new(),
+            pools: Mutex::new(HashMap::new()),
+        }
     }
 
     pub async fn positions(&self, token_id: U256) -> PositionsData {
         return self
             .contract
-            .method::<U256, (U256, Address, Address, Address, U256, U256, U256, U256, U256, U256, U256, U256)>("positions", token_id)
+            .method::<U256, PositionsTuple>("positions", token_id)
             .unwrap()
             .call()
             .await
             .unwrap().into();
     }
-}
\ No newline at end of file
+
+    pub async fn factory(&self) -> Address {
+        if let Some(factory) = self.factory.get() {
+            return *factory;
+        }
+        let factory = self.contract.method::<(), Address>("factory", ()).unwrap().call().await.unwrap();
+        *self.factory.get_or_init(|| factory)
+    }
+
+    // pool address and ticks of a position
+    async fn pool_of(&self, token_id: U256) -> (Address, i32, i32) {
+        let cached = self.pools.lock().unwrap().get(&token_id).copied();
+        if let Some(pool) = cached {
+            return pool;
+        }
+        let position = self.positions(token_id).await;
+        let factory = self.factory().await;
+        let pool = (
+            univ3::pool_address(factory, position.token0, position.token1, position.fee),
+            position.tick_lower,
+            position.tick_upper,
+        );
+        self.pools.lock().unwrap().insert(token_id, pool);
+        pool
+    }
+
+    // A position and the pool state its fees are computed from, read with one multicall
+    // so the position's fee checkpoints and the pool's counters come from the same block.
+    pub async fn position_state(&self, token_id: U256, block: Option<BlockNumber>) -> (PositionsData, FeeState) {
+        let (pool, tick_lower, tick_upper) = self.pool_of(token_id).await;
+        let abi = from_str::<Abi>(include_str!("./abi/pool.json")).unwrap();
+        let pool = Contract::<T>::new(pool, abi, self.contract.client());
+        let mut multicall = Multicall::new(self.contract.client(), Some(MULTICALL_ADDRESS)).await.unwrap();
+        multicall
+            .add_call(self.contract.method::<U256, PositionsTuple>("positions", token_id).unwrap(), false)
+            .add_call(pool.method::<(), Slot0Tuple>("slot0", ()).unwrap(), false)
+            .add_call(pool.method::<(), U256>("feeGrowthGlobal0X128", ()).unwrap(), false)
+            .add_call(pool.method::<(), U256>("feeGrowthGlobal1X128", ()).unwrap(), false)
+            .add_call(pool.method::<i32, TicksTuple>("ticks", tick_lower).unwrap(), false)
+            .add_call(pool.method::<i32, TicksTuple>("ticks", tick_upper).unwrap(), false);
+        if let Some(block) = block {
+            multicall = multicall.block(block);
+        }
+        let (position, slot0, global0, global1, lower, upper): (PositionsTuple, Slot0Tuple, U256, U256, TicksTuple, TicksTuple) =
+            multicall.call().await.unwrap();
+        (
+            position.into(),
+            FeeState {
+                tick: slot0.1,
+                fee_growth_global_x128: (global0, global1),
+                lower_outside_x128: (lower.2, lower.3),
+                upper_outside_x128: (upper.2, upper.3),
+            },
+        )
+    }
+
+    pub async fn balance_of(&self, owner: Address) -> U256 {
+        self.contract.method::<Address, U256>("balanceOf", owner).unwrap().call().await.unwrap()
+    }
+
+    pub async fn token_of_owner_by_index(&self, owner: Address, index: U256) -> U256 {
+        self.contract
+            .method::<(Address, U256), U256>("tokenOfOwnerByIndex", (owner, index))
+            .unwrap()
+            .call()
+            .await
+            .unwrap()
+    }
+
+    // every position currently held by `owner`, enumerated via balanceOf/tokenOfOwnerByIndex
+    pub async fn tokens_of_owner(&self, owner: Address) -> Vec<U256> {
+        let balance = self.balance_of(owner).await;
+        let mut token_ids = Vec::<U256>::new();
+        for index in 0..balance.as_u64() {
+            token_ids.push(self.token_of_owner_by_index(owner, U256::from(index)).await);
+        }
+        token_ids
+    }
+
+    pub async fn is_approved_for_all(&self, owner: Address, operator: Address) -> bool {
+        self.contract
+            .method::<(Address, Address), bool>("isApprovedForAll", (owner, operator))
+            .unwrap()
+            .call()
+            .await
+            .unwrap()
+    }
+
+    pub async fn get_approved(&self, token_id: U256) -> Address {
+        self.contract
+            .method::<U256, Address>("getApproved", token_id)
+            .unwrap()
+            .call()
+            .await
+            .unwrap()
+    }
+
+    pub async fn set_approval_for_all(
+        &self,
+        operator: Address,
+        approved: bool,
+    ) -> Result<Option<TransactionReceipt>, ContractError<T>> {
+        let call = self
+            .contract
+            .method::<(Address, bool), ()>("setApprovalForAll", (operator, approved))?;
+        let pending = call.send().await?;
+        pending.await.map_err(ContractError::from)
+    }
+
+    // fees owed to the position, as returned by a static collect from its owner
+    pub async fn collect_preview(&self, token_id: U256, owner: Address) -> (U256, U256) {
+        self.contract
+            .method::<((U256, Address, u128, u128),), (U256, U256)>(
+                "collect",
+                ((token_id, owner, u128::MAX, u128::MAX),),
+            )
+            .unwrap()
+            .from(owner)
+            .call()
+            .await
+            .unwrap()
+    }
+
+    pub async fn collect(
+        &self,
+        token_id: U256,
+        recipient: Address,
+    ) -> Result<Option<TransactionReceipt>, ContractError<T>> {
+        let call = self.contract.method::<((U256, Address, u128, u128),), (U256, U256)>(
+            "collect",
+            ((token_id, recipient, u128::MAX, u128::MAX),),
+        )?;
+        let pending = call.send().await?;
+        pending.await.map_err(ContractError::from)
+    }
+
+    // decreaseLiquidity only credits tokensOwed, so it is batched with a collect to pay out.
+    // Reverts if the liquidity removed is worth less than `amounts_min`.
+    pub async fn decrease_liquidity(
+        &self,
+        token_id: U256,
+        liquidity: u128,
+        amounts_min: (U256, U256),
+        recipient: Address,
+        deadline: U256,
+    ) -> Result<Option<TransactionReceipt>, ContractError<T>> {
+        let decrease = self.contract.encode(
+            "decreaseLiquidity",
+            ((token_id, liquidity, amounts_min.0, amounts_min.1, deadline),),
+        )?;
+        let collect = self.contract.encode(
+            "collect",
+            ((token_id, recipient, u128::MAX, u128::MAX),),
+        )?;
+        self.multicall(vec![decrease, collect]).await
+    }
+
+    // reverts if less than `amounts_min` of the desired amounts can be added
+    pub async fn increase_liquidity(
+        &self,
+        token_id: U256,
+        amounts_desired: (U256, U256),
+        amounts_min: (U256, U256),
+        deadline: U256,
+    ) -> Result<Option<TransactionReceipt>, ContractError<T>> {
+        let call = self
+            .contract
+            .method::<((U256, U256, U256, U256, U256, U256),), (u128, U256, U256)>(
+                "increaseLiquidity",
+                ((token_id, amounts_desired.0, amounts_desired.1, amounts_min.0, amounts_min.1, deadline),),
+            )?;
+        let pending = call.send().await?;
+        pending.await.map_err(ContractError::from)
+    }
+
+    pub async fn multicall(
+        &self,
+        data: Vec<Bytes>,
+    ) -> Result<Option<TransactionReceipt>, ContractError<T>> {
+        let call = self.contract.method::<Vec<Bytes>, Vec<Bytes>>("multicall", data)?;
+        let pending = call.send().await?;
+        pending.await.map_err(ContractError::from)
+    }
+}
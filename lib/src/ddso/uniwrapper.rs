@@ -4,7 +4,7 @@ use ethers::{
     abi::{Abi, Address},
     contract::Contract,
     providers::Middleware,
-    types::U256,
+    types::{BlockNumber, U256},
 };
 use serde_json::from_str;
 
@@ -23,13 +23,33 @@ impl<T: Middleware + Clone> UniWrapper<T> {
         Self { address, contract }
     }
 
-    pub async fn total(&self, npfm: Address, token_id: U256, sqrt_price_x96: U256) -> (U256, U256) {
-        return self
+    pub async fn total(&self, npfm: Address, token_id: U256, sqrt_price_x96: U256, block: Option<BlockNumber>) -> (U256, U256) {
+        let call = self
             .contract
             .method::<(Address, U256, U256), (U256, U256)>("total", (npfm, token_id, sqrt_price_x96))
+            .unwrap();
+        let call = match block {
+            Some(block) => call.block(block),
+            None => call,
+        };
+        call.call().await.unwrap()
+    }
+
+    pub async fn get_sqrt_ratio_at_tick(&self, tick: i32) -> U256 {
+        self.contract
+            .method::<i32, U256>("getSqrtRatioAtTick", tick)
             .unwrap()
             .call()
             .await
-            .unwrap().into();
+            .unwrap()
+    }
+
+    pub async fn compute_address(&self, factory: Address, token0: Address, token1: Address, fee: u32) -> Address {
+        self.contract
+            .method::<(Address, Address, Address, u32), Address>("computeAddress", (factory, token0, token1, fee))
+            .unwrap()
+            .call()
+            .await
+            .unwrap()
     }
 }
\ No newline at end of file
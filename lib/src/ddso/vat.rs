@@ -58,18 +58,70 @@ impl<T: Middleware + Clone> Vat<T> {
             .unwrap();
     }
 
+    pub async fn debt(&self) -> U256 {
+        self.contract
+            .method::<(), U256>("debt", ())
+            .unwrap()
+            .call()
+            .await
+            .unwrap()
+    }
+
+    pub async fn ceil(&self) -> U256 {
+        self.contract
+            .method::<(), U256>("ceil", ())
+            .unwrap()
+            .call()
+            .await
+            .unwrap()
+    }
+
+    // surplus rico, wad
+    pub async fn joy(&self) -> U256 {
+        self.contract
+            .method::<(), U256>("joy", ())
+            .unwrap()
+            .call()
+            .await
+            .unwrap()
+    }
+
+    // bad debt, rad
+    pub async fn sin(&self) -> U256 {
+        self.contract
+            .method::<(), U256>("sin", ())
+            .unwrap()
+            .call()
+            .await
+            .unwrap()
+    }
+
+    // rounding dust left from drips, rad
+    pub async fn rest(&self) -> U256 {
+        self.contract
+            .method::<(), U256>("rest", ())
+            .unwrap()
+            .call()
+            .await
+            .unwrap()
+    }
+
     pub async fn ink(&self, ilk: &str, urn: Address) -> Vec<U256> {
+        self.try_ink(ilk, urn).await.unwrap()
+    }
+
+    // ink that surfaces reverts and undecodable returns instead of panicking
+    pub async fn try_ink(&self, ilk: &str, urn: Address) -> Result<Vec<U256>, ContractError<T>> {
         let ilk = string_to_bytes32(ilk);
+        let raw_ilk = self
+            .contract
+            .method::<(H256, Address), Bytes>("ink", (ilk, urn))?
+            .call()
+            .await?;
         match ilk.eq(&string_to_bytes32(":uninft")) {
             true => {
-                let raw_ilk = self
-                    .contract
-                    .method::<(H256, Address), Bytes>("ink", (ilk, urn))
-                    .unwrap()
-                    .call()
-                    .await
-                    .unwrap();
-                let decoded_tokens = ethers::abi::decode(&[ParamType::Array(Box::new(ParamType::Uint(256)))], &raw_ilk.0).unwrap();
+                let decoded_tokens = ethers::abi::decode(&[ParamType::Array(Box::new(ParamType::Uint(256)))], &raw_ilk.0)
+                    .map_err(AbiError::from)?;
                 let mut token_ids: Vec<U256> = Vec::new();
                 if let Token::Array(values) = &decoded_tokens[0] {
                     for token in values {
@@ -77,21 +129,10 @@ impl<T: Middleware + Clone> Vat<T> {
                             token_ids.push(*value);
                         }
                     }
-                } else {
-                    println!("Unexpected token type")
                 }
-                return token_ids;
-            }
-            false => {
-                let raw_ilk = self
-                    .contract
-                    .method::<(H256, Address), Bytes>("ink", (ilk, urn))
-                    .unwrap()
-                    .call()
-                    .await
-                    .unwrap();
-                return vec![U256::from_big_endian(&raw_ilk)];
+                Ok(token_ids)
             }
+            false => Ok(vec![U256::from_big_endian(&raw_ilk)]),
         }
     }
 
@@ -107,7 +148,6 @@ impl<T: Middleware + Clone> Vat<T> {
         urn
     }
 
-    #[allow(dead_code)]
     pub async fn safe(&self, ilk: &str, usr: Address) -> (U256, U256, U256) {
         let ilk = string_to_bytes32(ilk);
         let safe = self
@@ -133,6 +173,58 @@ impl<T: Middleware + Clone> Vat<T> {
         geth.into()
     }
 
+    // geth that surfaces reverts, hooks revert on keys they don't have (e.g. "gem" on :uninft)
+    pub async fn try_geth(
+        &self,
+        ilk: &str,
+        char: &str,
+        indexes: Vec<H256>,
+    ) -> Result<H256, ContractError<T>> {
+        let ilk = string_to_bytes32(ilk);
+        let char = string_to_bytes32(char);
+        self.contract
+            .method::<(H256, H256, Vec<H256>), H256>("geth", (ilk, char, indexes))?
+            .call()
+            .await
+    }
+
+    pub async fn bail(
+        &self,
+        ilk: &str,
+        usr: Address,
+    ) -> Result<Option<TransactionReceipt>, ContractError<T>> {
+        let ilk = string_to_bytes32(ilk);
+        let call = self
+            .contract
+            .method::<(H256, Address), Bytes>("bail", (ilk, usr))?;
+        let pending = call.send().await?;
+        pending.await.map_err(ContractError::from)
+    }
+
+    // reverts (and so errors) when the urn can't be bailed by the sender
+    pub async fn estimate_bail_gas(&self, ilk: &str, usr: Address) -> Result<U256, ContractError<T>> {
+        let ilk = string_to_bytes32(ilk);
+        self.contract
+            .method::<(H256, Address), Bytes>("bail", (ilk, usr))?
+            .estimate_gas()
+            .await
+    }
+
+    pub async fn frob(
+        &self,
+        ilk: &str,
+        usr: Address,
+        dink: Bytes,
+        dart: I256,
+    ) -> Result<Option<TransactionReceipt>, ContractError<T>> {
+        let ilk = string_to_bytes32(ilk);
+        let call = self
+            .contract
+            .method::<(H256, Address, Bytes, I256), ()>("frob", (ilk, usr, dink, dart))?;
+        let pending = call.send().await?;
+        pending.await.map_err(ContractError::from)
+    }
+
     pub async fn ilks(&self, ilk: &str) -> Ilk {
         let ilk = string_to_bytes32(ilk);
         let _ilk = self
@@ -146,6 +238,20 @@ impl<T: Middleware + Clone> Vat<T> {
         _ilk
     }
 }
+// The :uninft hook reads dink as `uint[]`, where the first element is the direction
+// (1 to add, uint(-1) to remove) and the rest are the token ids to move.
+pub fn uninft_dink(add: bool, token_ids: &[U256]) -> Bytes {
+    let direction = match add {
+        true => U256::one(),
+        false => U256::MAX,
+    };
+    let tokens = std::iter::once(direction)
+        .chain(token_ids.iter().copied())
+        .map(Token::Uint)
+        .collect::<Vec<Token>>();
+    Bytes::from(ethers::abi::encode(&[Token::Array(tokens)]))
+}
+
 pub struct RU256(U256);
 impl From<H256> for RU256 {
     fn from(h: H256) -> Self {
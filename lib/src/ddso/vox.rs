@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use ethers::{
-    abi::{Abi, Address}, contract::Contract, providers::Middleware, types::{H256, U256},
+    abi::{Abi, Address}, contract::{Contract, ContractError}, providers::Middleware, types::{TransactionReceipt, H256, U256},
 };
 use serde_json::from_str;
 
@@ -63,4 +63,21 @@ impl<T: Middleware + Clone> Vox<T> {
             .unwrap();
         how
     }
+
+    pub async fn cap(&self) -> U256 {
+        let cap = self
+            .contract
+            .method::<(), U256>("cap", ())
+            .unwrap()
+            .call()
+            .await
+            .unwrap();
+        cap
+    }
+
+    pub async fn poke(&self) -> Result<Option<TransactionReceipt>, ContractError<T>> {
+        let call = self.contract.method::<(), ()>("poke", ())?;
+        let pending = call.send().await?;
+        pending.await.map_err(ContractError::from)
+    }
 }
@@ -0,0 +1,150 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use ethers::{
+    providers::Middleware,
+    types::{Address, BlockNumber, Filter, U64},
+};
+
+use crate::{
+    ddso::{
+        events::{IntoNewPalm1Vec, IntoNewPalm2Vec, NEW_PALM_1_SIG, NEW_PALM_2_SIG},
+        gem::Gem,
+        vat::Vat,
+    },
+    utils::{bytes32_to_string, string_to_bytes32},
+};
+
+// ERC20 metadata of an ilk's gem
+#[derive(Debug, Clone)]
+pub struct GemMeta {
+    pub address: Address,
+    pub decimals: u32,
+    pub symbol: String,
+    pub name: String,
+}
+
+// Gem metadata by ilk, each ilk is resolved once. Ilks without a gem (:uninft) are
+// cached as None.
+#[derive(Debug, Default)]
+pub struct GemCache {
+    gems: Mutex<HashMap<String, Option<GemMeta>>>,
+}
+
+impl GemCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn get<T: Middleware + Clone, M: Middleware + Clone>(
+        &self,
+        provider: &Arc<M>,
+        vat: &Vat<T>,
+        ilk: &str,
+    ) -> Option<GemMeta> {
+        if let Some(meta) = self.gems.lock().unwrap().get(ilk) {
+            return meta.clone();
+        }
+        let meta = match vat.try_geth(ilk, "gem", Vec::new()).await {
+            Ok(gem) if !gem.is_zero() => {
+                let gem = Gem::new(provider, Address::from_slice(&gem.as_bytes()[..20]));
+                Some(GemMeta {
+                    address: gem.address,
+                    decimals: gem.decimals().await.as_u32(),
+                    symbol: gem.symbol().await,
+                    name: gem.name().await,
+                })
+            }
+            _ => None,
+        };
+        self.gems
+            .lock()
+            .unwrap()
+            .insert(ilk.to_string(), meta.clone());
+        meta
+    }
+
+    pub fn cached(&self, ilk: &str) -> Option<GemMeta> {
+        self.gems.lock().unwrap().get(ilk).cloned().flatten()
+    }
+
+    // falls back to 18, the decimals of rico and of every wad, for unresolved ilks
+    pub fn decimals(&self, ilk: &str) -> u32 {
+        self.cached(ilk).map_or(18, |meta| meta.decimals)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DiscoveredIlk {
+    pub ilk: String,
+    // None for ilks whose hook has no single gem, e.g. :uninft
+    pub gem: Option<Address>,
+    pub symbol: Option<String>,
+    // block of the first palm keyed on the ilk, i.e. its init
+    pub block_number: U64,
+}
+
+// Every ilk the vat has initialized, in init order. Vat.init files rack and hook for
+// the new ilk, so the NewPalm1 events for those keys name every ilk at least once.
+pub async fn discover_ilks<M: Middleware + Clone>(
+    provider: &Arc<M>,
+    vat: &Vat<M>,
+) -> Result<Vec<DiscoveredIlk>, M::Error> {
+    let filter = Filter::new()
+        .address(vec![vat.address])
+        .topic0(*NEW_PALM_1_SIG)
+        .topic1(vec![string_to_bytes32("rack"), string_to_bytes32("hook")])
+        .from_block(BlockNumber::Earliest)
+        .to_block(BlockNumber::Latest);
+    let mut logs = provider.get_logs(&filter).await?;
+    logs.sort_by_key(|log| (log.block_number, log.log_index));
+
+    let mut ilks = Vec::<DiscoveredIlk>::new();
+    for palm in logs.into_new_palm1_vec() {
+        let ilk = bytes32_to_string(palm.ilk);
+        if ilks.iter().any(|known| known.ilk == ilk) {
+            continue;
+        }
+        let gem = match vat.try_geth(&ilk, "gem", Vec::new()).await {
+            Ok(gem) if !gem.is_zero() => Some(Address::from_slice(&gem.as_bytes()[..20])),
+            _ => None,
+        };
+        let symbol = match gem {
+            Some(gem) => Some(Gem::new(provider, gem).symbol().await),
+            None => None,
+        };
+        ilks.push(DiscoveredIlk {
+            ilk,
+            gem,
+            symbol,
+            block_number: palm.block_number,
+        });
+    }
+    Ok(ilks)
+}
+
+// Tokens the :uninft hook has a feed for. Per-token hook config is filed as
+// NewPalm2(key, ilk, token), so every token shows up under its "src" palm.
+pub async fn discover_uni_tokens<M: Middleware>(
+    provider: &M,
+    vat: Address,
+) -> Result<Vec<Address>, M::Error> {
+    let filter = Filter::new()
+        .address(vec![vat])
+        .topic0(*NEW_PALM_2_SIG)
+        .topic1(string_to_bytes32("src"))
+        .topic2(string_to_bytes32(":uninft"))
+        .from_block(BlockNumber::Earliest)
+        .to_block(BlockNumber::Latest);
+    let mut logs = provider.get_logs(&filter).await?;
+    logs.sort_by_key(|log| (log.block_number, log.log_index));
+    let mut tokens = Vec::<Address>::new();
+    for palm in logs.into_new_palm2_vec() {
+        if !tokens.contains(&palm.usr) {
+            tokens.push(palm.usr);
+        }
+    }
+    Ok(tokens)
+}
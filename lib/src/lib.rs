@@ -1,4 +1,12 @@
 pub mod utils;
 pub mod math;
 pub mod ddso;
-pub mod valuation;
\ No newline at end of file
+pub mod valuation;
+pub mod planner;
+pub mod validation;
+pub mod auction;
+pub mod poke;
+pub mod urns;
+pub mod ilks;
+pub mod stress;
+pub mod univ3;
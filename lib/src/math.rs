@@ -33,3 +33,19 @@ impl units {
         }
     }
 }
+
+// x^n for a ray x, by squaring
+pub fn rpow(x: U256, n: U256) -> U256 {
+    let ray = units::new().RAY;
+    let mut result = ray;
+    let mut base = x;
+    let mut n = n;
+    while !n.is_zero() {
+        if n.bit(0) {
+            result = result * base / ray;
+        }
+        base = base * base / ray;
+        n >>= 1;
+    }
+    result
+}
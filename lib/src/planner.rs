@@ -0,0 +1,175 @@
+use ethers::types::U256;
+
+use crate::math::units;
+
+// Everything needed to plan around a single urn. `value` is the liqr-adjusted
+// collateral value (as returned by Valuer), `line` and `dust` are rads.
+#[derive(Debug, Clone, Default)]
+pub struct Planner {
+    pub ink: U256,
+    pub art: U256,
+    pub value: U256,
+    pub rack: U256,
+    pub par: U256,
+    pub dust: U256,
+    pub line: U256,
+    pub tart: U256,
+}
+
+impl Planner {
+    // urn debt in rico
+    pub fn debt(&self) -> U256 {
+        self.art * self.rack / units::new().RAY
+    }
+
+    // urn debt in ref, what value is compared against
+    pub fn loan(&self) -> U256 {
+        self.debt() * self.par / units::new().RAY
+    }
+
+    // additional rico that can be drawn before the urn is unsafe or the ilk hits its line
+    pub fn max_borrow(&self) -> U256 {
+        let units = units::new();
+        if self.par.is_zero() || self.rack.is_zero() {
+            return U256::zero();
+        }
+        let max_debt = self.value * units.RAY / self.par;
+        let room = max_debt.saturating_sub(self.debt());
+        let line_room = self.line.saturating_sub(self.tart * self.rack) / units.RAY;
+        let borrow = std::cmp::min(room, line_room);
+        // a fresh urn has to draw at least dust
+        match (self.debt() + borrow) * units.RAY < self.dust {
+            true => U256::zero(),
+            false => borrow,
+        }
+    }
+
+    // additional art that corresponds to max_borrow
+    pub fn max_borrow_art(&self) -> U256 {
+        match self.rack.is_zero() {
+            true => U256::zero(),
+            false => self.max_borrow() * units::new().RAY / self.rack,
+        }
+    }
+
+    // rico to wipe so that value / loan >= safety (wad). Returns the whole debt if the
+    // remainder would fall under dust, since a partial repay can't go there.
+    pub fn repay_for_safety(&self, safety: U256) -> U256 {
+        let units = units::new();
+        if safety.is_zero() || self.par.is_zero() {
+            return U256::zero();
+        }
+        let target_loan = self.value * units.WAD / safety;
+        let target_debt = target_loan * units.RAY / self.par;
+        let repay = self.debt().saturating_sub(target_debt);
+        let rest = self.debt() - repay;
+        match !repay.is_zero() && !rest.is_zero() && rest * units.RAY < self.dust {
+            true => self.debt(),
+            false => repay,
+        }
+    }
+
+    // additional (value, ink) needed so that value / loan >= safety (wad), ink is
+    // priced at the urn's current value per ink
+    pub fn collateral_for_safety(&self, safety: U256) -> (U256, U256) {
+        let units = units::new();
+        let target_value = self.loan() * safety / units.WAD;
+        let value = target_value.saturating_sub(self.value);
+        let ink = match self.value.is_zero() {
+            true => U256::zero(),
+            false => value * self.ink / self.value,
+        };
+        (value, ink)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wad(x: u64) -> U256 {
+        U256::from(x) * units::new().WAD
+    }
+
+    fn rad(x: u64) -> U256 {
+        U256::from(x) * units::new().RAD
+    }
+
+    // rack and par of 1, 100 rico dust and plenty of line
+    fn planner(art: U256, value: U256) -> Planner {
+        let units = units::new();
+        Planner {
+            ink: wad(1),
+            art,
+            value,
+            rack: units.RAY,
+            par: units.RAY,
+            dust: rad(100),
+            line: rad(1_000_000),
+            tart: art,
+        }
+    }
+
+    #[test]
+    fn max_borrow_below_dust() {
+        // a fresh urn that can't draw dust can't draw at all
+        assert_eq!(planner(U256::zero(), wad(99)).max_borrow(), U256::zero());
+        assert_eq!(planner(U256::zero(), wad(100)).max_borrow(), wad(100));
+        // an urn under dust has to end up at or over it
+        assert_eq!(planner(wad(20), wad(100)).max_borrow(), wad(80));
+        assert_eq!(planner(wad(20), wad(99)).max_borrow(), U256::zero());
+    }
+
+    #[test]
+    fn max_borrow_without_headroom() {
+        assert_eq!(planner(wad(150), wad(150)).max_borrow(), U256::zero());
+        // underwater urns don't go negative
+        assert_eq!(planner(wad(150), wad(100)).max_borrow(), U256::zero());
+        let mut full = planner(wad(150), wad(1000));
+        full.tart = wad(1_000_000);
+        assert_eq!(full.max_borrow(), U256::zero());
+        full.tart = wad(1_000_000) - wad(10);
+        assert_eq!(full.max_borrow(), wad(10));
+        assert_eq!(Planner::default().max_borrow(), U256::zero());
+    }
+
+    #[test]
+    fn repay_for_safety_respects_dust() {
+        let safety = |x: u64| U256::from(x) * units::new().WAD / 10;
+        let urn = planner(wad(150), wad(150));
+        // 1.5 leaves exactly dust
+        assert_eq!(urn.repay_for_safety(safety(15)), wad(50));
+        // 2 would leave 75, under dust, so everything has to go
+        assert_eq!(urn.repay_for_safety(safety(20)), wad(150));
+        // already safe
+        assert_eq!(urn.repay_for_safety(safety(10)), U256::zero());
+        assert_eq!(urn.repay_for_safety(U256::zero()), U256::zero());
+    }
+
+    #[test]
+    fn rounding_with_rack_over_one() {
+        let units = units::new();
+        let mut urn = planner(wad(100), wad(120));
+        urn.rack = units.RAY * 11 / 10;
+        assert_eq!(urn.debt(), wad(110));
+        assert_eq!(urn.max_borrow(), wad(10));
+        // art rounds down, so drawing it never exceeds max_borrow
+        let art = urn.max_borrow_art();
+        assert_eq!(art, U256::from(9090909090909090909_u64));
+        assert_eq!(art * urn.rack / units.RAY, wad(10) - 1);
+        urn.value = wad(120) + 1;
+        assert_eq!(urn.max_borrow_art() * urn.rack / units.RAY, wad(10) + 1);
+        // debt is floored too
+        urn.art = U256::one();
+        urn.rack = units.RAY * 3 / 2;
+        assert_eq!(urn.debt(), U256::one());
+    }
+
+    #[test]
+    fn collateral_for_safety_at_current_price() {
+        let urn = planner(wad(100), wad(120));
+        let (value, ink) = urn.collateral_for_safety(units::new().WAD * 3 / 2);
+        assert_eq!(value, wad(30));
+        assert_eq!(ink, wad(1) / 4);
+    }
+}
@@ -0,0 +1,115 @@
+use ethers::types::U256;
+
+use crate::math::{rpow, units};
+
+// par and way as they would be right after a poke at `now`
+#[derive(Debug, Clone)]
+pub struct PokeProjection {
+    pub dt: U256,
+    pub par: U256,
+    pub way: U256,
+}
+
+// Mirrors vox.poke: par grows at the old way for the time since tau, then way moves
+// by how^dt towards mar, bounded by cap (or 1/cap when falling).
+pub fn project_poke(
+    par: U256,
+    way: U256,
+    how: U256,
+    cap: U256,
+    tau: U256,
+    mar: U256,
+    now: U256,
+) -> PokeProjection {
+    let ray = units::new().RAY;
+    let dt = now.saturating_sub(tau);
+    let next_par = par * rpow(way, dt) / ray;
+    let next_way = match mar.cmp(&next_par) {
+        std::cmp::Ordering::Less => std::cmp::min(cap, way * rpow(how, dt) / ray),
+        std::cmp::Ordering::Greater => std::cmp::max(
+            ray * ray / cap,
+            way * rpow(ray * ray / how, dt) / ray,
+        ),
+        std::cmp::Ordering::Equal => way,
+    };
+    PokeProjection {
+        dt,
+        par: next_par,
+        way: next_way,
+    }
+}
+
+// annualized percentage rate of a per-second ray
+pub fn way_to_apr(way: U256) -> f64 {
+    let units = units::new();
+    ((way.as_u128() as f64 / units.RAY_F64).powf(units.BANKYEAR) - 1.0) * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ray(x: u64) -> U256 {
+        U256::from(x) * units::new().RAY
+    }
+
+    #[test]
+    fn par_compounds_at_the_old_way() {
+        // way of 2 doubles par every second
+        let projection = project_poke(ray(1), ray(2), ray(1), ray(1), U256::from(100), ray(1024), U256::from(110));
+        assert_eq!(projection.dt, U256::from(10));
+        assert_eq!(projection.par, ray(1024));
+        // mar == par leaves way alone
+        assert_eq!(projection.way, ray(2));
+    }
+
+    #[test]
+    fn way_moves_against_mar() {
+        let units = units::new();
+        // mar under par raises way by how^dt
+        let up = project_poke(ray(1), ray(1), ray(2), ray(16), U256::zero(), ray(1) / 2, U256::from(3));
+        assert_eq!(up.way, ray(8));
+        // mar over par lowers it by (1/how)^dt
+        let down = project_poke(ray(1), ray(1), ray(2), ray(16), U256::zero(), ray(2), U256::from(3));
+        assert_eq!(down.way, units.RAY / 8);
+    }
+
+    #[test]
+    fn way_is_bounded_by_cap() {
+        let units = units::new();
+        let up = project_poke(ray(1), ray(1), ray(2), ray(4), U256::zero(), ray(1) / 2, U256::from(3));
+        assert_eq!(up.way, ray(4));
+        let down = project_poke(ray(1), ray(1), ray(2), ray(4), U256::zero(), ray(2), U256::from(3));
+        assert_eq!(down.way, units.RAY / 4);
+    }
+
+    #[test]
+    fn rico_how_over_an_hour() {
+        let units = units::new();
+        let how = U256::from_dec_str("1000000000000003652500000000").unwrap();
+        let cap = U256::from_dec_str("1000000022000000000000000000").unwrap();
+        let tau = U256::from(1_700_000_000);
+        let now = tau + 3600;
+        let up = project_poke(units.RAY, units.RAY, how, cap, tau, units.RAY / 2, now);
+        assert_eq!(up.dt, U256::from(3600));
+        assert_eq!(up.way, U256::from_dec_str("1000000000013149000000085949").unwrap());
+        let down = project_poke(units.RAY, units.RAY, how, cap, tau, units.RAY * 2, now);
+        assert_eq!(down.way, U256::from_dec_str("999999999986851000000085949").unwrap());
+    }
+
+    #[test]
+    fn tau_in_the_future_is_no_time() {
+        let projection = project_poke(ray(1), ray(2), ray(2), ray(4), U256::from(200), ray(1), U256::from(100));
+        assert!(projection.dt.is_zero());
+        assert_eq!((projection.par, projection.way), (ray(1), ray(2)));
+    }
+
+    #[test]
+    fn apr_of_way() {
+        let units = units::new();
+        assert_eq!(way_to_apr(units.RAY), 0.0);
+        let way = 1.05_f64.powf(1.0 / units.BANKYEAR);
+        let way = U256::from((way * 1e18) as u128) * U256::exp10(9);
+        assert!((way_to_apr(way) - 5.0).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+
+use ethers::{
+    providers::Middleware,
+    types::{Address, U256},
+};
+
+use crate::{math::units, valuation::Valuer};
+
+// Percentage price moves applied to feeds before valuing collateral, e.g. -20.0 for a 20% drop.
+// A feed is matched by its full tag (`weth:ref`) or the part before the ':' (`weth`),
+// anything unmatched gets the global shock.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Shocks {
+    pub global: f64,
+    pub feeds: HashMap<String, f64>,
+}
+
+impl Shocks {
+    // `-20` shocks every feed, `weth=-20 usdc=0` shocks feeds by tag, the two can be mixed
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut shocks = Shocks::default();
+        for token in input.split_whitespace() {
+            let (feed, pct) = match token.split_once('=') {
+                Some((feed, pct)) => (Some(feed), pct),
+                None => (None, token),
+            };
+            let pct = pct
+                .trim_end_matches('%')
+                .parse::<f64>()
+                .map_err(|_| format!("{} is not a percentage", pct))?;
+            if pct < -100.0 {
+                return Err(format!("{}% would make a price negative", pct));
+            }
+            match feed {
+                Some("*") | None => shocks.global = pct,
+                Some("") => return Err(format!("{} is missing a feed", token)),
+                Some(feed) => {
+                    shocks.feeds.insert(feed.to_string(), pct);
+                }
+            }
+        }
+        Ok(shocks)
+    }
+
+    pub fn pct(&self, tag: &str) -> f64 {
+        let prefix = tag.split(':').next().unwrap_or(tag);
+        *self
+            .feeds
+            .get(tag)
+            .or_else(|| self.feeds.get(prefix))
+            .unwrap_or(&self.global)
+    }
+
+    // shocked price, to the basis point
+    pub fn apply(&self, tag: &str, price: U256) -> U256 {
+        let bps = (self.pct(tag) * 100.0).round() as i64;
+        price * U256::from((10_000 + bps).max(0)) / U256::from(10_000)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StressedUrn {
+    pub ilk: String,
+    pub usr: Address,
+    pub loan: U256,
+    pub value: U256,
+    pub shocked_value: U256,
+}
+
+impl StressedUrn {
+    fn safety(&self, value: U256) -> f64 {
+        let units = units::new();
+        match self.loan.is_zero() {
+            true => 0.0,
+            false => (units.BLN * value / self.loan).as_u128() as f64 / units.BLN_F64,
+        }
+    }
+
+    pub fn safety_before(&self) -> f64 {
+        self.safety(self.value)
+    }
+
+    pub fn safety_after(&self) -> f64 {
+        self.safety(self.shocked_value)
+    }
+
+    // value is already discounted by liqr, so the urn can be bailed once it drops under the loan
+    pub fn liquidatable(&self) -> bool {
+        !self.loan.is_zero() && self.shocked_value < self.loan
+    }
+}
+
+// revalues an urn at current and shocked prices, `loan` is the urn's loan in ref (wad)
+pub async fn stress_urn<T: Middleware + Clone>(
+    valuer: &Valuer<'_, T>,
+    ilk: &str,
+    usr: Address,
+    loan: U256,
+    shocks: &Shocks,
+) -> StressedUrn {
+    StressedUrn {
+        ilk: ilk.to_string(),
+        usr,
+        loan,
+        value: valuer.value_urn(ilk, usr, &Shocks::default()).await,
+        shocked_value: valuer.value_urn(ilk, usr, shocks).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wad(x: u64) -> U256 {
+        U256::from(x) * U256::exp10(18)
+    }
+
+    #[test]
+    fn global_shock_applies_to_every_feed() {
+        let shocks = Shocks::parse("-20").unwrap();
+        assert_eq!(shocks.global, -20.0);
+        assert!(shocks.feeds.is_empty());
+        assert_eq!(shocks.apply("weth:ref", wad(100)), wad(80));
+        assert_eq!(shocks.apply("usdc:ref", wad(100)), wad(80));
+    }
+
+    #[test]
+    fn feed_shocks_override_the_global_one() {
+        let shocks = Shocks::parse("-10 weth=-50 usdc:ref=5%").unwrap();
+        assert_eq!(shocks.pct("weth:ref"), -50.0);
+        assert_eq!(shocks.pct("weth"), -50.0);
+        assert_eq!(shocks.pct("usdc:ref"), 5.0);
+        assert_eq!(shocks.pct("wbtc:ref"), -10.0);
+        assert_eq!(shocks.apply("weth:ref", wad(100)), wad(50));
+        assert_eq!(shocks.apply("usdc:ref", wad(100)), wad(105));
+        // `*=` is the global shock spelled as a feed
+        assert_eq!(Shocks::parse("*=-10").unwrap().global, -10.0);
+    }
+
+    #[test]
+    fn negative_and_large_shocks() {
+        let shocks = Shocks::parse("weth=-100 usdc=150").unwrap();
+        assert_eq!(shocks.apply("weth:ref", wad(100)), U256::zero());
+        assert_eq!(shocks.apply("usdc:ref", wad(100)), wad(250));
+        // no shock leaves prices untouched, down to the basis point
+        assert_eq!(Shocks::default().apply("weth:ref", U256::from(12345)), U256::from(12345));
+        assert_eq!(Shocks::parse("0.01").unwrap().apply("weth", U256::from(10_000)), U256::from(10_001));
+        assert!(Shocks::parse("-100.5").is_err());
+        assert!(Shocks::parse("weth=-101").is_err());
+    }
+
+    #[test]
+    fn malformed_input_is_rejected() {
+        for input in ["abc", "weth=", "weth=abc", "=-20", "-20%%x", "weth=-20=1"] {
+            assert!(Shocks::parse(input).is_err(), "{} parsed", input);
+        }
+        assert_eq!(Shocks::parse("").unwrap(), Shocks::default());
+    }
+}
@@ -0,0 +1,450 @@
+use ethers::{
+    abi::{encode, Token},
+    providers::Middleware,
+    types::{Address, BlockNumber, U256, U512},
+    utils::{get_create2_address_from_hash, hex, keccak256},
+};
+
+use crate::ddso::{nfpm::NPFM, uniwrapper::UniWrapper};
+
+// Uniswap v3 TickMath, LiquidityAmounts, fee math and PoolAddress, ported to match the
+// solidity libraries the uniwrapper links against bit for bit. A position's fees come from
+// pool state read once per valuation (see NPFM::position_state), so revaluing it at other
+// prices (stress shocks, range charts) is done here without RPC.
+
+pub const MIN_TICK: i32 = -887272;
+pub const MAX_TICK: i32 = 887272;
+
+pub fn min_sqrt_ratio() -> U256 {
+    U256::from(4295128739_u64)
+}
+
+pub fn max_sqrt_ratio() -> U256 {
+    U256::from_dec_str("1461446703485210103287273052203988822378723970342").unwrap()
+}
+
+fn q96() -> U256 {
+    U256::one() << 96
+}
+
+fn q128() -> U256 {
+    U256::one() << 128
+}
+
+// keccak256 of the pool creation code, the same on every chain the canonical factory is on
+const POOL_INIT_CODE_HASH: &str = "e34f199b19b2b4f47f68442619d555527d244f78a3297ea89325f843f87b8b54";
+
+// PoolAddress.computeAddress, the CREATE2 address the factory deploys a pool at
+pub fn pool_address(factory: Address, token0: Address, token1: Address, fee: u32) -> Address {
+    let salt = keccak256(encode(&[
+        Token::Address(token0),
+        Token::Address(token1),
+        Token::Uint(U256::from(fee)),
+    ]));
+    get_create2_address_from_hash(factory, salt, hex::decode(POOL_INIT_CODE_HASH).unwrap())
+}
+
+// FullMath.mulDiv, a * b / denominator without overflowing the intermediate product
+pub fn mul_div(a: U256, b: U256, denominator: U256) -> U256 {
+    let product = U512::from(a) * U512::from(b);
+    U256::try_from(product / U512::from(denominator)).unwrap()
+}
+
+// price of token0 in token1 at a tick, adjusted for the tokens' decimals
+pub fn tick_to_price(tick: i32, decimals0: u32, decimals1: u32) -> f64 {
+    1.0001_f64.powi(tick) * 10_f64.powi(decimals0 as i32 - decimals1 as i32)
+}
+
+// TickMath.getSqrtRatioAtTick, sqrt(1.0001^tick) as a X96 fixed point number
+pub fn get_sqrt_ratio_at_tick(tick: i32) -> U256 {
+    let abs_tick = tick.unsigned_abs();
+    assert!(abs_tick <= MAX_TICK as u32, "tick {} out of range", tick);
+    let hex = |x: &str| U256::from_str_radix(x, 16).unwrap();
+    let mut ratio = match abs_tick & 0x1 != 0 {
+        true => hex("fffcb933bd6fad37aa2d162d1a594001"),
+        false => U256::one() << 128,
+    };
+    let factors = [
+        (0x2, "fff97272373d413259a46990580e213a"),
+        (0x4, "fff2e50f5f656932ef12357cf3c7fdcc"),
+        (0x8, "ffe5caca7e10e4e61c3624eaa0941cd0"),
+        (0x10, "ffcb9843d60f6159c9db58835c926644"),
+        (0x20, "ff973b41fa98c081472e6896dfb254c0"),
+        (0x40, "ff2ea16466c96a3843ec78b326b52861"),
+        (0x80, "fe5dee046a99a2a811c461f1969c3053"),
+        (0x100, "fcbe86c7900a88aedcffc83b479aa3a4"),
+        (0x200, "f987a7253ac413176f2b074cf7815e54"),
+        (0x400, "f3392b0822b70005940c7a398e4b70f3"),
+        (0x800, "e7159475a2c29b7443b29c7fa6e889d9"),
+        (0x1000, "d097f3bdfd2022b8845ad8f792aa5825"),
+        (0x2000, "a9f746462d870fdf8a65dc1f90e061e5"),
+        (0x4000, "70d869a156d2a1b890bb3df62baf32f7"),
+        (0x8000, "31be135f97d08fd981231505542fcfa6"),
+        (0x10000, "9aa508b5b7a84e1c677de54f3e99bc9"),
+        (0x20000, "5d6af8dedb81196699c329225ee604"),
+        (0x40000, "2216e584f5fa1ea926041bedfe98"),
+        (0x80000, "48a170391f7dc42444e8fa2"),
+    ];
+    for (bit, factor) in factors {
+        if abs_tick & bit != 0 {
+            ratio = (ratio * hex(factor)) >> 128;
+        }
+    }
+    if tick > 0 {
+        ratio = U256::MAX / ratio;
+    }
+    // round up so getTickAtSqrtRatio of the result is consistent
+    let rounding = match (ratio % (U256::one() << 32)).is_zero() {
+        true => U256::zero(),
+        false => U256::one(),
+    };
+    (ratio >> 32) + rounding
+}
+
+// TickMath.getTickAtSqrtRatio, the greatest tick whose ratio is <= sqrt_price_x96.
+// Starts from a float estimate and settles it against get_sqrt_ratio_at_tick, which
+// gives the same tick as the solidity log2 without porting it.
+pub fn get_tick_at_sqrt_ratio(sqrt_price_x96: U256) -> i32 {
+    assert!(
+        sqrt_price_x96 >= min_sqrt_ratio() && sqrt_price_x96 < max_sqrt_ratio(),
+        "sqrt price {} out of range",
+        sqrt_price_x96
+    );
+    let price = sqrt_price_x96
+        .0
+        .iter()
+        .rev()
+        .fold(0.0, |acc, limb| acc * 2_f64.powi(64) + *limb as f64)
+        / 2_f64.powi(96);
+    let mut tick = ((2.0 * price.ln() / 1.0001_f64.ln()).floor() as i32).clamp(MIN_TICK, MAX_TICK);
+    while tick > MIN_TICK && get_sqrt_ratio_at_tick(tick) > sqrt_price_x96 {
+        tick -= 1;
+    }
+    while tick < MAX_TICK && get_sqrt_ratio_at_tick(tick + 1) <= sqrt_price_x96 {
+        tick += 1;
+    }
+    tick
+}
+
+// LiquidityAmounts.getAmount0ForLiquidity
+pub fn get_amount0_for_liquidity(sqrt_ratio_a: U256, sqrt_ratio_b: U256, liquidity: u128) -> U256 {
+    let (a, b) = match sqrt_ratio_a > sqrt_ratio_b {
+        true => (sqrt_ratio_b, sqrt_ratio_a),
+        false => (sqrt_ratio_a, sqrt_ratio_b),
+    };
+    mul_div(U256::from(liquidity) << 96, b - a, b) / a
+}
+
+// LiquidityAmounts.getAmount1ForLiquidity
+pub fn get_amount1_for_liquidity(sqrt_ratio_a: U256, sqrt_ratio_b: U256, liquidity: u128) -> U256 {
+    let (a, b) = match sqrt_ratio_a > sqrt_ratio_b {
+        true => (sqrt_ratio_b, sqrt_ratio_a),
+        false => (sqrt_ratio_a, sqrt_ratio_b),
+    };
+    mul_div(U256::from(liquidity), b - a, q96())
+}
+
+// LiquidityAmounts.getAmountsForLiquidity, token amounts a position's liquidity is worth
+// at sqrt_price_x96
+pub fn get_amounts_for_liquidity(
+    sqrt_price_x96: U256,
+    sqrt_ratio_a: U256,
+    sqrt_ratio_b: U256,
+    liquidity: u128,
+) -> (U256, U256) {
+    let (a, b) = match sqrt_ratio_a > sqrt_ratio_b {
+        true => (sqrt_ratio_b, sqrt_ratio_a),
+        false => (sqrt_ratio_a, sqrt_ratio_b),
+    };
+    if sqrt_price_x96 <= a {
+        (get_amount0_for_liquidity(a, b, liquidity), U256::zero())
+    } else if sqrt_price_x96 < b {
+        (
+            get_amount0_for_liquidity(sqrt_price_x96, b, liquidity),
+            get_amount1_for_liquidity(a, sqrt_price_x96, liquidity),
+        )
+    } else {
+        (U256::zero(), get_amount1_for_liquidity(a, b, liquidity))
+    }
+}
+
+// principal of a position between two ticks, what the wrapper's total is without fees
+pub fn principal(sqrt_price_x96: U256, tick_lower: i32, tick_upper: i32, liquidity: u128) -> (U256, U256) {
+    get_amounts_for_liquidity(
+        sqrt_price_x96,
+        get_sqrt_ratio_at_tick(tick_lower),
+        get_sqrt_ratio_at_tick(tick_upper),
+        liquidity,
+    )
+}
+
+// Tick.getFeeGrowthInside, fee growth per unit of liquidity between two ticks.
+// Fee growth counters are allowed to overflow, so everything wraps like the solidity.
+pub fn fee_growth_inside(
+    tick_current: i32,
+    tick_lower: i32,
+    tick_upper: i32,
+    fee_growth_global_x128: U256,
+    lower_outside_x128: U256,
+    upper_outside_x128: U256,
+) -> U256 {
+    let below = match tick_current >= tick_lower {
+        true => lower_outside_x128,
+        false => fee_growth_global_x128.overflowing_sub(lower_outside_x128).0,
+    };
+    let above = match tick_current < tick_upper {
+        true => upper_outside_x128,
+        false => fee_growth_global_x128.overflowing_sub(upper_outside_x128).0,
+    };
+    fee_growth_global_x128
+        .overflowing_sub(below)
+        .0
+        .overflowing_sub(above)
+        .0
+}
+
+// fees a position has earned since its fee growth was last checkpointed,
+// add the position's tokensOwed for everything collectable
+pub fn fees_owed(fee_growth_inside_x128: U256, fee_growth_inside_last_x128: U256, liquidity: u128) -> U256 {
+    mul_div(
+        fee_growth_inside_x128.overflowing_sub(fee_growth_inside_last_x128).0,
+        U256::from(liquidity),
+        q128(),
+    )
+}
+
+// Pool state a position's fees are computed from, token0 and token1 fee growth as pairs.
+// Read in the same batch as the position so its checkpoints match the pool's counters.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeeState {
+    // the pool's current tick, from slot0
+    pub tick: i32,
+    pub fee_growth_global_x128: (U256, U256),
+    // feeGrowthOutside of the position's lower and upper ticks
+    pub lower_outside_x128: (U256, U256),
+    pub upper_outside_x128: (U256, U256),
+}
+
+impl FeeState {
+    pub fn fee_growth_inside(&self, tick_lower: i32, tick_upper: i32) -> (U256, U256) {
+        (
+            fee_growth_inside(
+                self.tick,
+                tick_lower,
+                tick_upper,
+                self.fee_growth_global_x128.0,
+                self.lower_outside_x128.0,
+                self.upper_outside_x128.0,
+            ),
+            fee_growth_inside(
+                self.tick,
+                tick_lower,
+                tick_upper,
+                self.fee_growth_global_x128.1,
+                self.lower_outside_x128.1,
+                self.upper_outside_x128.1,
+            ),
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MathCheck {
+    pub pool_matches: bool,
+    pub ticks_match: bool,
+    // sqrt prices the native principal plus fees differed from uniwrapper.total at
+    pub total_mismatches: Vec<U256>,
+}
+
+impl MathCheck {
+    pub fn ok(&self) -> bool {
+        self.pool_matches && self.ticks_match && self.total_mismatches.is_empty()
+    }
+}
+
+// Checks the native math against the uniwrapper for one position, everything read at
+// `block` so the position can't earn fees between the two. The pool address and tick
+// ratios must match computeAddress and getSqrtRatioAtTick, and principal plus fees must
+// equal the wrapper's total at every price.
+pub async fn verify<T: Middleware + Clone>(
+    npfm: &NPFM<T>,
+    uniwrapper: &UniWrapper<T>,
+    token_id: U256,
+    sqrt_prices_x96: &[U256],
+    block: BlockNumber,
+) -> MathCheck {
+    let (position, state) = npfm.position_state(token_id, Some(block)).await;
+    let factory = npfm.factory().await;
+    let pool_matches = pool_address(factory, position.token0, position.token1, position.fee)
+        == uniwrapper.compute_address(factory, position.token0, position.token1, position.fee).await;
+    let (tick_lower, tick_upper) = (position.tick_lower, position.tick_upper);
+    let ticks_match = get_sqrt_ratio_at_tick(tick_lower) == uniwrapper.get_sqrt_ratio_at_tick(tick_lower).await
+        && get_sqrt_ratio_at_tick(tick_upper) == uniwrapper.get_sqrt_ratio_at_tick(tick_upper).await;
+    let mut total_mismatches = Vec::<U256>::new();
+    for sqrt_price_x96 in sqrt_prices_x96 {
+        let total = uniwrapper.total(npfm.address, token_id, *sqrt_price_x96, Some(block)).await;
+        if position.total(*sqrt_price_x96, &state) != total {
+            total_mismatches.push(*sqrt_price_x96);
+        }
+    }
+    MathCheck {
+        pool_matches,
+        ticks_match,
+        total_mismatches,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // encodePriceSqrt from the uniswap v3 tests, sqrt(reserve1 / reserve0) as X96
+    fn encode_price_sqrt(reserve1: u64, reserve0: u64) -> U256 {
+        let ratio = (U512::from(reserve1) << 192) / U512::from(reserve0);
+        U256::try_from(ratio.integer_sqrt()).unwrap()
+    }
+
+    #[test]
+    fn sqrt_ratio_at_tick_bounds() {
+        assert_eq!(get_sqrt_ratio_at_tick(MIN_TICK), U256::from(4295128739_u64));
+        assert_eq!(get_sqrt_ratio_at_tick(MIN_TICK), min_sqrt_ratio());
+        assert_eq!(get_sqrt_ratio_at_tick(MAX_TICK), max_sqrt_ratio());
+        assert_eq!(get_sqrt_ratio_at_tick(0), q96());
+    }
+
+    #[test]
+    fn sqrt_ratio_at_tick_one() {
+        assert_eq!(
+            get_sqrt_ratio_at_tick(1),
+            U256::from_dec_str("79232123823359799118286999568").unwrap()
+        );
+        assert_eq!(
+            get_sqrt_ratio_at_tick(-1),
+            U256::from_dec_str("79224201403219477170569942574").unwrap()
+        );
+    }
+
+    #[test]
+    fn tick_ratio_round_trip() {
+        for tick in [MIN_TICK, -200_000, -100, -1, 0, 1, 100, 200_311, MAX_TICK - 1] {
+            let ratio = get_sqrt_ratio_at_tick(tick);
+            assert_eq!(get_tick_at_sqrt_ratio(ratio), tick);
+            // anything under a tick's ratio belongs to the tick below
+            if tick > MIN_TICK {
+                assert_eq!(get_tick_at_sqrt_ratio(ratio - 1), tick - 1);
+            }
+        }
+    }
+
+    // SqrtPriceMath.getAmount0Delta/getAmount1Delta rounded down, price 1 to 1.21
+    #[test]
+    fn amounts_for_liquidity_between_prices() {
+        let (a, b) = (encode_price_sqrt(1, 1), encode_price_sqrt(121, 100));
+        let liquidity = 10_u128.pow(18);
+        assert_eq!(get_amount0_for_liquidity(a, b, liquidity), U256::from(90909090909090909_u64));
+        assert_eq!(get_amount1_for_liquidity(a, b, liquidity), U256::from(99999999999999999_u64));
+        // order of the bounds doesn't matter
+        assert_eq!(get_amount0_for_liquidity(b, a, liquidity), U256::from(90909090909090909_u64));
+    }
+
+    // LiquidityAmounts.getAmountsForLiquidity vectors for a 100/110 - 110/100 range
+    #[test]
+    fn amounts_for_liquidity_around_range() {
+        let (a, b) = (encode_price_sqrt(100, 110), encode_price_sqrt(110, 100));
+        assert_eq!(
+            get_amounts_for_liquidity(encode_price_sqrt(1, 1), a, b, 2148),
+            (U256::from(99), U256::from(99))
+        );
+        assert_eq!(
+            get_amounts_for_liquidity(encode_price_sqrt(99, 110), a, b, 1048),
+            (U256::from(99), U256::zero())
+        );
+        assert_eq!(
+            get_amounts_for_liquidity(encode_price_sqrt(111, 100), a, b, 2097),
+            (U256::zero(), U256::from(199))
+        );
+    }
+
+    #[test]
+    fn pool_address_of_usdc_weth() {
+        // the mainnet usdc/weth 0.05% pool
+        let address = |x: &str| x.parse::<Address>().unwrap();
+        assert_eq!(
+            pool_address(
+                address("0x1F98431c8aD98523631AE4a59f267346ea31F984"),
+                address("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"),
+                address("0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2"),
+                500,
+            ),
+            address("0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640")
+        );
+    }
+
+    #[test]
+    fn fee_growth_inside_range() {
+        let global = U256::from(100);
+        // in range, both ticks' outside growth is below and above the range
+        assert_eq!(fee_growth_inside(0, -10, 10, global, U256::from(10), U256::from(20)), U256::from(70));
+        // under the range the lower tick's outside growth is what's above it
+        assert_eq!(fee_growth_inside(-20, -10, 10, global, U256::from(70), U256::from(20)), U256::from(50));
+        // over the range the upper tick's outside growth is what's below it
+        assert_eq!(fee_growth_inside(20, -10, 10, global, U256::from(10), U256::from(40)), U256::from(30));
+        // the upper tick itself counts as over the range
+        assert_eq!(fee_growth_inside(10, -10, 10, global, U256::from(10), U256::from(40)), U256::from(30));
+    }
+
+    #[test]
+    fn fee_growth_wraps() {
+        // the global counter overflowed past the lower tick's checkpoint
+        let lower_outside = U256::MAX - U256::from(10);
+        assert_eq!(
+            fee_growth_inside(0, -10, 10, U256::from(5), lower_outside, U256::zero()),
+            U256::from(16)
+        );
+        assert_eq!(fees_owed(q128() * 3, U256::zero(), 7), U256::from(21));
+        // inside growth overflowed since the position's checkpoint
+        let last = U256::MAX - q128() + 1;
+        assert_eq!(fees_owed(q128(), last, 7), U256::from(14));
+    }
+
+    #[test]
+    fn fee_state_pairs_tokens() {
+        let state = FeeState {
+            tick: 0,
+            fee_growth_global_x128: (U256::from(100), U256::from(1000)),
+            lower_outside_x128: (U256::from(10), U256::from(100)),
+            upper_outside_x128: (U256::from(20), U256::from(200)),
+        };
+        assert_eq!(state.fee_growth_inside(-10, 10), (U256::from(70), U256::from(700)));
+    }
+
+    // Checks the native math against the deployed uniwrapper for every position the
+    // diamond holds, e.g. ARB_RPC_URL=https://arb1.arbitrum.io/rpc cargo test -- --ignored
+    #[tokio::test]
+    #[ignore]
+    async fn matches_uniwrapper() {
+        use ethers::providers::{Http, Provider};
+        use std::sync::Arc;
+
+        let url = std::env::var("ARB_RPC_URL").expect("ARB_RPC_URL");
+        let provider = Arc::new(Provider::<Http>::try_from(url).unwrap());
+        let address = |x: &str| x.parse::<Address>().unwrap();
+        let npfm = NPFM::new(&provider, address("0xc36442b4a4522e871399cd717abdd847ab11fe88"));
+        let uniwrapper = UniWrapper::new(&provider, address("0xf18eA2cf7A87C3F11fF8FF6B073DdEDfE2497f03"));
+        let block = BlockNumber::Number(provider.get_block_number().await.unwrap());
+
+        let token_ids = npfm.tokens_of_owner(address("0x598C6c1cd9459F882530FC9D7dA438CB74C6CB3b")).await;
+        assert!(!token_ids.is_empty(), "the diamond holds no positions to check");
+        for token_id in token_ids {
+            let (position, state) = npfm.position_state(token_id, Some(block)).await;
+            // under, at and over the range, every branch of getAmountsForLiquidity
+            let sqrt_prices = [
+                get_sqrt_ratio_at_tick((position.tick_lower - 1000).max(MIN_TICK)),
+                get_sqrt_ratio_at_tick(state.tick),
+                get_sqrt_ratio_at_tick((position.tick_upper + 1000).min(MAX_TICK)),
+            ];
+            let check = verify(&npfm, &uniwrapper, token_id, &sqrt_prices, block).await;
+            assert!(check.ok(), "token {}: {:?}", token_id, check);
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+
+use ethers::{
+    providers::Middleware,
+    types::{Address, BlockNumber, Filter, H256, U256, U64},
+};
+
+use crate::{
+    ddso::{
+        events::{IntoNewPalm2Vec, NewPalm2, NEW_PALM_2_SIG},
+        vat::Vat,
+    },
+    utils::{bytes32_to_string, string_to_bytes32},
+};
+
+#[derive(Debug, Clone)]
+pub struct IndexedUrn {
+    pub ilk: String,
+    pub usr: Address,
+    // gem ink only, :uninft ink is emitted as bytes and isn't replayed
+    pub ink: U256,
+    pub art: U256,
+    // last block that touched the urn
+    pub block_number: U64,
+}
+
+impl IndexedUrn {
+    pub fn is_open(&self) -> bool {
+        !self.ink.is_zero() || !self.art.is_zero()
+    }
+}
+
+// on-chain state of an indexed urn, from Vat::ink/Vat::urns
+#[derive(Debug, Clone)]
+pub struct UrnCheck {
+    pub ink: Vec<U256>,
+    pub art: U256,
+    pub ink_matches: bool,
+    pub art_matches: bool,
+}
+
+// Every urn the vat has written, rebuilt by replaying NewPalm2 ink/art events.
+// Palms carry the new value rather than a delta, so the last event for an urn wins.
+#[derive(Debug, Clone, Default)]
+pub struct UrnIndex {
+    urns: HashMap<(H256, Address), IndexedUrn>,
+    pub synced_block: Option<U64>,
+}
+
+impl UrnIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn apply(&mut self, palm: &NewPalm2) {
+        let act = bytes32_to_string(palm.act);
+        if act != "ink" && act != "art" {
+            return;
+        }
+        let urn = self
+            .urns
+            .entry((palm.ilk, palm.usr))
+            .or_insert_with(|| IndexedUrn {
+                ilk: bytes32_to_string(palm.ilk),
+                usr: palm.usr,
+                ink: U256::zero(),
+                art: U256::zero(),
+                block_number: palm.block_number,
+            });
+        match act.as_str() {
+            "ink" if urn.ilk == ":uninft" => {}
+            "ink" => urn.ink = palm.val.into_raw(),
+            _ => urn.art = palm.val.into_raw(),
+        }
+        urn.block_number = palm.block_number;
+    }
+
+    // replays everything since the last sync up to and including `to_block`
+    pub async fn sync<M: Middleware>(
+        &mut self,
+        provider: &M,
+        vat: Address,
+        to_block: U64,
+    ) -> Result<(), M::Error> {
+        // called every block, nothing to replay until the chain moves
+        if self.synced_block.is_some_and(|synced_block| synced_block >= to_block) {
+            return Ok(());
+        }
+        let filter = Filter::new()
+            .address(vec![vat])
+            .topic0(*NEW_PALM_2_SIG)
+            .topic1(vec![string_to_bytes32("ink"), string_to_bytes32("art")])
+            .from_block(match self.synced_block {
+                Some(synced_block) => BlockNumber::Number(synced_block + 1),
+                None => BlockNumber::Earliest,
+            })
+            .to_block(to_block);
+        let mut logs = provider.get_logs(&filter).await?;
+        logs.sort_by_key(|log| (log.block_number, log.log_index));
+        for palm in logs.into_new_palm2_vec() {
+            self.apply(&palm);
+        }
+        self.synced_block = Some(to_block);
+        Ok(())
+    }
+
+    pub fn get(&self, ilk: &str, usr: Address) -> Option<&IndexedUrn> {
+        self.urns.get(&(string_to_bytes32(ilk), usr))
+    }
+
+    pub fn urns(&self) -> impl Iterator<Item = &IndexedUrn> {
+        self.urns.values()
+    }
+
+    pub fn open(&self) -> impl Iterator<Item = &IndexedUrn> {
+        self.urns().filter(|urn| urn.is_open())
+    }
+
+    pub fn by_ilk<'a>(&'a self, ilk: &'a str) -> impl Iterator<Item = &'a IndexedUrn> {
+        self.urns().filter(move |urn| urn.ilk == ilk)
+    }
+
+    pub fn by_usr(&self, usr: Address) -> impl Iterator<Item = &IndexedUrn> {
+        self.urns().filter(move |urn| urn.usr == usr)
+    }
+
+    pub fn ilks(&self) -> Vec<String> {
+        let mut ilks = self
+            .urns()
+            .map(|urn| urn.ilk.clone())
+            .collect::<Vec<String>>();
+        ilks.sort();
+        ilks.dedup();
+        ilks
+    }
+
+    // compares the indexed urn against the vat, :uninft ink isn't indexed so it always matches
+    pub async fn verify<T: Middleware + Clone>(
+        &self,
+        vat: &Vat<T>,
+        ilk: &str,
+        usr: Address,
+    ) -> UrnCheck {
+        let ink = vat.ink(ilk, usr).await;
+        let art = vat.urns(ilk, usr).await;
+        let (indexed_ink, indexed_art) = match self.get(ilk, usr) {
+            Some(urn) => (urn.ink, urn.art),
+            None => (U256::zero(), U256::zero()),
+        };
+        UrnCheck {
+            ink_matches: ilk == ":uninft" || ink.first().copied().unwrap_or_default() == indexed_ink,
+            art_matches: art == indexed_art,
+            ink,
+            art,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::I256;
+
+    fn palm(block: u64, act: &str, ilk: &str, usr: u64, val: u64) -> NewPalm2 {
+        NewPalm2 {
+            block_number: U64::from(block),
+            act: string_to_bytes32(act),
+            ilk: string_to_bytes32(ilk),
+            usr: Address::from_low_u64_be(usr),
+            val: I256::from(val),
+        }
+    }
+
+    #[test]
+    fn last_palm_wins() {
+        let mut index = UrnIndex::new();
+        index.apply(&palm(1, "ink", "weth", 1, 10));
+        index.apply(&palm(2, "art", "weth", 1, 5));
+        index.apply(&palm(3, "ink", "weth", 1, 8));
+        let urn = index.get("weth", Address::from_low_u64_be(1)).unwrap();
+        assert_eq!((urn.ink, urn.art, urn.block_number), (U256::from(8), U256::from(5), U64::from(3)));
+        assert_eq!(index.open().count(), 1);
+
+        index.apply(&palm(4, "art", "weth", 1, 0));
+        index.apply(&palm(4, "ink", "weth", 1, 0));
+        assert_eq!(index.open().count(), 0);
+        // closed urns are kept, they just aren't open
+        assert_eq!(index.urns().count(), 1);
+    }
+
+    #[test]
+    fn urns_are_keyed_by_ilk_and_usr() {
+        let mut index = UrnIndex::new();
+        index.apply(&palm(1, "ink", "weth", 1, 10));
+        index.apply(&palm(1, "ink", "wbtc", 1, 1));
+        index.apply(&palm(1, "ink", "weth", 2, 3));
+        // anything but ink and art is ignored
+        index.apply(&palm(1, "rack", "weth", 3, 3));
+        assert_eq!(index.urns().count(), 3);
+        assert_eq!(index.by_ilk("weth").count(), 2);
+        assert_eq!(index.by_usr(Address::from_low_u64_be(1)).count(), 2);
+        assert_eq!(index.ilks(), vec!["wbtc".to_string(), "weth".to_string()]);
+    }
+
+    #[test]
+    fn uninft_ink_is_not_replayed() {
+        let mut index = UrnIndex::new();
+        index.apply(&palm(1, "ink", ":uninft", 1, 12345));
+        let urn = index.get(":uninft", Address::from_low_u64_be(1)).unwrap();
+        assert!(urn.ink.is_zero());
+        assert!(!urn.is_open());
+        index.apply(&palm(2, "art", ":uninft", 1, 7));
+        let urn = index.get(":uninft", Address::from_low_u64_be(1)).unwrap();
+        assert_eq!((urn.art, urn.block_number), (U256::from(7), U64::from(2)));
+        assert!(urn.is_open());
+    }
+
+    #[test]
+    fn replaying_a_palm_is_idempotent() {
+        let palms = [palm(1, "ink", "weth", 1, 10), palm(2, "art", "weth", 1, 5)];
+        let mut index = UrnIndex::new();
+        for palm in palms.iter().chain(palms.iter()) {
+            index.apply(palm);
+        }
+        index.apply(&palms[1]);
+        let urn = index.get("weth", Address::from_low_u64_be(1)).unwrap();
+        assert_eq!((urn.ink, urn.art), (U256::from(10), U256::from(5)));
+        assert_eq!(index.urns().count(), 1);
+    }
+}
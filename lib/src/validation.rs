@@ -0,0 +1,210 @@
+use std::fmt;
+
+use ethers::types::{I256, U256};
+
+use crate::{ddso::vat::Ilk, math::units};
+
+// A hypothetical frob on a single urn, dink is in ink units and dart in art units.
+#[derive(Debug, Clone)]
+pub struct Frob {
+    pub ink: U256,
+    pub art: U256,
+    pub dink: I256,
+    pub dart: I256,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrobViolation {
+    // urn would hold more ink or art than it has
+    InkUnderflow { ink: U256, dink: I256 },
+    ArtUnderflow { art: U256, dart: I256 },
+    // urn debt (rad) would be non-zero but under the ilk dust (rad)
+    Dust { tab: U256, dust: U256 },
+    // ilk debt (rad) would exceed the ilk line (rad)
+    IlkLine { tab: U256, line: U256 },
+    // global debt (wad) would exceed the vat ceil (wad)
+    Ceil { debt: U256, ceil: U256 },
+}
+
+impl fmt::Display for FrobViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let units = units::new();
+        let wad = |x: &U256| ethers::utils::format_units(*x, 18).unwrap();
+        let rad = |x: &U256| ethers::utils::format_units(*x / units.RAY, 18).unwrap();
+        match self {
+            FrobViolation::InkUnderflow { ink, dink } => {
+                write!(f, "dink {} removes more than ink {}", dink, ink)
+            }
+            FrobViolation::ArtUnderflow { art, dart } => {
+                write!(f, "dart {} wipes more than art {}", dart, art)
+            }
+            FrobViolation::Dust { tab, dust } => {
+                write!(f, "urn debt {} is under dust {}", rad(tab), rad(dust))
+            }
+            FrobViolation::IlkLine { tab, line } => {
+                write!(f, "ilk debt {} is over line {}", rad(tab), rad(line))
+            }
+            FrobViolation::Ceil { debt, ceil } => {
+                write!(f, "total debt {} is over ceil {}", wad(debt), wad(ceil))
+            }
+        }
+    }
+}
+
+fn apply(x: U256, dx: I256) -> Option<U256> {
+    match dx.is_negative() {
+        true => x.checked_sub(dx.unsigned_abs()),
+        false => x.checked_add(dx.into_raw()),
+    }
+}
+
+// Checks a frob locally against the limits the vat enforces. `debt` and `ceil` are the
+// vat globals; debt limits are only checked when the frob draws (dart > 0), like the vat.
+pub fn check_frob(ilk: &Ilk, frob: &Frob, debt: U256, ceil: U256) -> Vec<FrobViolation> {
+    let units = units::new();
+    let mut violations = Vec::<FrobViolation>::new();
+    if apply(frob.ink, frob.dink).is_none() {
+        violations.push(FrobViolation::InkUnderflow {
+            ink: frob.ink,
+            dink: frob.dink,
+        });
+    }
+    let art = match apply(frob.art, frob.dart) {
+        Some(art) => art,
+        None => {
+            violations.push(FrobViolation::ArtUnderflow {
+                art: frob.art,
+                dart: frob.dart,
+            });
+            return violations;
+        }
+    };
+
+    let tab = art * ilk.rack;
+    if !frob.dart.is_zero() && !art.is_zero() && tab < ilk.dust {
+        violations.push(FrobViolation::Dust {
+            tab,
+            dust: ilk.dust,
+        });
+    }
+
+    if frob.dart.is_positive() {
+        let dtab = frob.dart.into_raw() * ilk.rack;
+        let ilk_tab = ilk.tart * ilk.rack + dtab;
+        if ilk_tab > ilk.line {
+            violations.push(FrobViolation::IlkLine {
+                tab: ilk_tab,
+                line: ilk.line,
+            });
+        }
+        let new_debt = debt + dtab / units.RAY;
+        if new_debt > ceil {
+            violations.push(FrobViolation::Ceil {
+                debt: new_debt,
+                ceil,
+            });
+        }
+    }
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::Address;
+
+    fn wad(x: u64) -> U256 {
+        U256::from(x) * units::new().WAD
+    }
+
+    fn rad(x: u64) -> U256 {
+        U256::from(x) * units::new().RAD
+    }
+
+    // 100 rico drawn against the ilk, 1000 line, 10 dust, rack 1
+    fn ilk() -> Ilk {
+        let units = units::new();
+        let zero = U256::zero();
+        Ilk::from((wad(100), units.RAY, rad(1000), rad(10), units.RAY, zero, units.RAY, Address::zero()))
+    }
+
+    fn frob(art: U256, dart: I256) -> Frob {
+        Frob {
+            ink: wad(1),
+            art,
+            dink: I256::zero(),
+            dart,
+        }
+    }
+
+    fn signed(x: U256) -> I256 {
+        I256::from_raw(x)
+    }
+
+    #[test]
+    fn dust_boundary() {
+        let ilk = ilk();
+        let (debt, ceil) = (wad(100), wad(10_000));
+        assert!(check_frob(&ilk, &frob(U256::zero(), signed(wad(10))), debt, ceil).is_empty());
+        assert_eq!(
+            check_frob(&ilk, &frob(U256::zero(), signed(wad(10) - 1)), debt, ceil),
+            vec![FrobViolation::Dust { tab: (wad(10) - 1) * units::new().RAY, dust: rad(10) }]
+        );
+        // wiping everything is fine, leaving a sliver under dust isn't
+        assert!(check_frob(&ilk, &frob(wad(20), -signed(wad(20))), debt, ceil).is_empty());
+        assert_eq!(check_frob(&ilk, &frob(wad(20), -signed(wad(15))), debt, ceil).len(), 1);
+        // frobs that don't touch art never trip dust, like the vat
+        assert!(check_frob(&ilk, &frob(wad(5), I256::zero()), debt, ceil).is_empty());
+    }
+
+    #[test]
+    fn dust_scales_with_rack() {
+        let mut ilk = ilk();
+        ilk.rack = units::new().RAY * 2;
+        let (debt, ceil) = (wad(100), wad(10_000));
+        assert!(check_frob(&ilk, &frob(U256::zero(), signed(wad(5))), debt, ceil).is_empty());
+        assert_eq!(check_frob(&ilk, &frob(U256::zero(), signed(wad(5) - 1)), debt, ceil).len(), 1);
+    }
+
+    #[test]
+    fn line_boundary() {
+        let ilk = ilk();
+        let (debt, ceil) = (wad(100), wad(10_000));
+        assert!(check_frob(&ilk, &frob(U256::zero(), signed(wad(900))), debt, ceil).is_empty());
+        assert_eq!(
+            check_frob(&ilk, &frob(U256::zero(), signed(wad(900) + 1)), debt, ceil),
+            vec![FrobViolation::IlkLine { tab: rad(1000) + units::new().RAY, line: rad(1000) }]
+        );
+        // an ilk already over its line can still be wiped
+        let mut over = ilk.clone();
+        over.line = rad(50);
+        assert!(check_frob(&over, &frob(wad(20), -signed(wad(10))), debt, ceil).is_empty());
+    }
+
+    #[test]
+    fn ceil_boundary() {
+        let mut ilk = ilk();
+        ilk.line = rad(1_000_000);
+        let (debt, ceil) = (wad(500), wad(1000));
+        assert!(check_frob(&ilk, &frob(U256::zero(), signed(wad(500))), debt, ceil).is_empty());
+        assert_eq!(
+            check_frob(&ilk, &frob(U256::zero(), signed(wad(500) + 1)), debt, ceil),
+            vec![FrobViolation::Ceil { debt: wad(1000) + 1, ceil: wad(1000) }]
+        );
+        assert!(check_frob(&ilk, &frob(wad(20), -signed(wad(10))), wad(2000), ceil).is_empty());
+    }
+
+    #[test]
+    fn underflows() {
+        let ilk = ilk();
+        let mut frob = frob(wad(10), -signed(wad(11)));
+        frob.dink = -signed(wad(2));
+        assert_eq!(
+            check_frob(&ilk, &frob, wad(100), wad(10_000)),
+            vec![
+                FrobViolation::InkUnderflow { ink: wad(1), dink: -signed(wad(2)) },
+                FrobViolation::ArtUnderflow { art: wad(10), dart: -signed(wad(11)) },
+            ]
+        );
+    }
+}
@@ -2,7 +2,56 @@ use std::cmp::max;
 
 use ethers::{providers::Middleware, types::{Address, H256, U256, U512}};
 
-use crate::{ddso::{feedbase::Feedbase, nfpm::NPFM, uniwrapper::UniWrapper, vat::{Vat, RU256}}, math::units};
+use crate::{ddso::{feedbase::Feedbase, nfpm::{PositionsData, RangeStatus, NPFM}, uniwrapper::UniWrapper, vat::{Vat, RU256}}, math::units, stress::Shocks, univ3, utils::bytes32_to_string};
+
+// sqrt(p0 / p1) as a X96 fixed point number, the price the hook values positions at.
+// Feeds price one base unit of each token in ref, so p0 / p1 is token1 per token0, the
+// orientation of the pool's sqrtPriceX96 that uniwrapper.total expects.
+pub fn sqrt_price_x96(t0_price: U256, t1_price: U256) -> U256 {
+    let t1_price: U512 = t1_price.into();
+    let t0_price: U512 = t0_price.into();
+    let scaled_t0_price: U512 = t0_price * U512::from(units::new().X96);
+    let scaled_ration = scaled_t0_price * U512::from(units::new().X96) / t1_price;
+    U256::try_from(scaled_ration.integer_sqrt()).unwrap()
+}
+
+// value of a position at arbitrary feed prices, computed natively like the hook would
+pub fn value_position(position: &PositionsData, fees: (U256, U256), t0_price: U256, t1_price: U256, liqr: U256) -> U256 {
+    let principal = position.principal(sqrt_price_x96(t0_price, t1_price));
+    ((principal.0 + fees.0) * t0_price + (principal.1 + fees.1) * t1_price) / liqr
+}
+
+// Feed and liqr the :uninft hook uses for one token, price and ttl as pulled from feedbase
+#[derive(Debug, Clone)]
+pub struct UniTokenInfo {
+    pub token: Address,
+    pub src: Address,
+    pub tag: H256,
+    pub liqr: U256,
+    pub price: U256,
+    pub ttl: U256,
+}
+
+// One :uninft position valued at the feed price, amounts are in token units
+#[derive(Debug, Clone)]
+pub struct UniNftBreakdown {
+    pub token_id: U256,
+    pub position: PositionsData,
+    // tick of the feed price the hook values the position at
+    pub tick: i32,
+    pub principal: (U256, U256),
+    pub fees: (U256, U256),
+    pub value: U256,
+    // token0 and token1 feed prices and the liqr the position is valued with
+    pub prices: (U256, U256),
+    pub liqr: U256,
+}
+
+impl UniNftBreakdown {
+    pub fn status(&self) -> RangeStatus {
+        self.position.range_status(self.tick)
+    }
+}
 
 pub struct Valuer<'a, T: Middleware + Clone> {
     pub npfm: &'a NPFM<T>,
@@ -21,58 +70,100 @@ impl <'a, T: Middleware + Clone> Valuer<'a, T> {
         }
     }
 
-    pub async fn value_uni_nft(
-        &self,
-        token_id: &U256,
-    ) -> U256 {
-        let position = self.npfm.positions(*token_id).await;
-        let token_0_xs = {
+    // feed src, tag and liqr configured for `token` under the :uninft ilk
+    pub async fn uni_token_info(&self, token: Address) -> (Address, H256, U256) {
+        let token_xs = {
             let mut bytes = [0u8; 32];
-            bytes[0..20].copy_from_slice(position.token0.as_bytes());
+            bytes[0..20].copy_from_slice(token.as_bytes());
             H256::from(bytes)
         };
-        let t0_info: (Address, H256, U256) = (
-            Address::from_slice(
-                &self.vat.geth::<H256>(":uninft", "src", vec![token_0_xs])
-                    .await
-                    .as_bytes()[0..20],
-            ),
-            self.vat.geth(":uninft", "tag", vec![token_0_xs]).await,
-            self.vat.geth::<RU256>(":uninft", "liqr", vec![token_0_xs])
-                .await
-                .into(),
-        );
-    
-        let token_1_xs = {
-            let mut bytes = [0u8; 32];
-            bytes[0..20].copy_from_slice(position.token1.as_bytes());
-            H256::from(bytes) // Con
-        };
-        let t1_info: (Address, H256, U256) = (
+        (
             Address::from_slice(
-                &self.vat.geth::<H256>(":uninft", "src", vec![token_1_xs])
+                &self.vat.geth::<H256>(":uninft", "src", vec![token_xs])
                     .await
                     .as_bytes()[0..20],
             ),
-            self.vat.geth(":uninft", "tag", vec![token_1_xs]).await,
-            self.vat.geth::<RU256>(":uninft", "liqr", vec![token_1_xs])
+            self.vat.geth(":uninft", "tag", vec![token_xs]).await,
+            self.vat.geth::<RU256>(":uninft", "liqr", vec![token_xs])
                 .await
                 .into(),
-        );
+        )
+    }
+
+    pub async fn uni_token(&self, token: Address) -> UniTokenInfo {
+        let (src, tag, liqr) = self.uni_token_info(token).await;
+        let (price, ttl) = self.feedbase.pull(src, tag).await;
+        UniTokenInfo {
+            token,
+            src,
+            tag,
+            liqr,
+            price: U256::from_big_endian(price.as_bytes()),
+            ttl,
+        }
+    }
+
+    // token0/token1 amounts (principal + fees) of a position at the feed price
+    pub async fn uni_nft_amounts(&self, token_id: &U256) -> (U256, U256) {
+        let (position, state) = self.npfm.position_state(*token_id, None).await;
+        let t0_info = self.uni_token_info(position.token0).await;
+        let t1_info = self.uni_token_info(position.token1).await;
+        let t1_price: U256 =
+            U256::from_big_endian(self.feedbase.pull(t1_info.0, t1_info.1).await.0.as_bytes());
+        let t0_price: U256 =
+            U256::from_big_endian(self.feedbase.pull(t0_info.0, t0_info.1).await.0.as_bytes());
+        position.total(sqrt_price_x96(t0_price, t1_price), &state)
+    }
+
+    pub async fn uni_nft_breakdown(&self, token_id: &U256) -> UniNftBreakdown {
+        let (position, state) = self.npfm.position_state(*token_id, None).await;
+        let t0_info = self.uni_token_info(position.token0).await;
+        let t1_info = self.uni_token_info(position.token1).await;
+        let t1_price: U256 =
+            U256::from_big_endian(self.feedbase.pull(t1_info.0, t1_info.1).await.0.as_bytes());
+        let t0_price: U256 =
+            U256::from_big_endian(self.feedbase.pull(t0_info.0, t0_info.1).await.0.as_bytes());
+        let sqrt_price = sqrt_price_x96(t0_price, t1_price);
+        let liqr = max(t0_info.2, t1_info.2);
+        let fees = position.fees(&state);
+        UniNftBreakdown {
+            token_id: *token_id,
+            tick: univ3::get_tick_at_sqrt_ratio(sqrt_price),
+            principal: position.principal(sqrt_price),
+            fees,
+            value: value_position(&position, fees, t0_price, t1_price, liqr),
+            position,
+            prices: (t0_price, t1_price),
+            liqr,
+        }
+    }
+
+    pub async fn value_uni_nft(
+        &self,
+        token_id: &U256,
+    ) -> U256 {
+        self.value_uni_nft_shocked(token_id, &Shocks::default()).await
+    }
+
+    // values a position with both feed prices shocked. Fees don't depend on the price,
+    // so only the principal moves with the shocks.
+    pub async fn value_uni_nft_shocked(
+        &self,
+        token_id: &U256,
+        shocks: &Shocks,
+    ) -> U256 {
+        let (position, state) = self.npfm.position_state(*token_id, None).await;
+        let t0_info = self.uni_token_info(position.token0).await;
+        let t1_info = self.uni_token_info(position.token1).await;
     
-        let t1_price_256: U256 =
+        let t1_feed: U256 =
             U256::from_big_endian(self.feedbase.pull(t1_info.0, t1_info.1).await.0.as_bytes());
-        let t0_price_256: U256 =
+        let t0_feed: U256 =
             U256::from_big_endian(self.feedbase.pull(t0_info.0, t0_info.1).await.0.as_bytes());
-        let t1_price: U512 = t1_price_256.try_into().unwrap();
-        let t0_price: U512 = t0_price_256.try_into().unwrap();
-        let scaled_t1_price: U512 = t1_price * U512::from(units::new().X96);
-        let scaled_ration = scaled_t1_price * U512::from(units::new().X96) / t0_price;
-        let price_256 = U256::try_from(scaled_ration.integer_sqrt()).unwrap();
-        let total = self.uniwrapper.total(self.npfm.address, *token_id, price_256).await;
+        let t0_price_256 = shocks.apply(&bytes32_to_string(t0_info.1), t0_feed);
+        let t1_price_256 = shocks.apply(&bytes32_to_string(t1_info.1), t1_feed);
         let liqr = max(t0_info.2, t1_info.2);
-        let value: U256 = (total.0 * t0_price_256 + total.1 * t1_price_256) / liqr;
-        value
+        value_position(&position, position.fees(&state), t0_price_256, t1_price_256, liqr)
     }
 
     pub async fn value_gem(
@@ -80,13 +171,110 @@ impl <'a, T: Middleware + Clone> Valuer<'a, T> {
         gem: &str,
         ink: &U256,
     ) -> U256 {
+        self.value_gem_shocked(gem, ink, &Shocks::default()).await
+    }
+
+    pub async fn value_gem_shocked(
+        &self,
+        gem: &str,
+        ink: &U256,
+        shocks: &Shocks,
+    ) -> U256 {
+        let (feed, liqr) = self.gem_feed(gem, shocks).await;
+        feed * ink / liqr
+    }
+
+    // shocked feed and liqr of a gem ilk, every urn of the ilk is worth feed * ink / liqr
+    pub async fn gem_feed(&self, gem: &str, shocks: &Shocks) -> (U256, U256) {
         let liqr: U256 = self.vat.geth::<RU256>(gem, "liqr", Vec::new()).await.into();
         let src: Address = Address::from_slice(
             &self.vat.geth::<H256>(gem, "src", Vec::new()).await.as_bytes()[0..20],
         );
         let tag: H256 = self.vat.geth::<H256>(gem, "tag", Vec::new()).await;
         let rfeed: H256 = self.feedbase.pull(src, tag).await.0;
-        let feed: U256 = U256::from_big_endian(rfeed.as_bytes());
-        feed * ink / liqr
+        let feed: U256 = shocks.apply(&bytes32_to_string(tag), U256::from_big_endian(rfeed.as_bytes()));
+        (feed, liqr)
     }
-}
\ No newline at end of file
+
+    // value of everything `usr` has locked in `ilk`
+    pub async fn value_urn(
+        &self,
+        ilk: &str,
+        usr: Address,
+        shocks: &Shocks,
+    ) -> U256 {
+        let ink = self.vat.ink(ilk, usr).await;
+        match ilk {
+            ":uninft" => {
+                let mut value = U256::zero();
+                for token_id in ink.iter() {
+                    value += self.value_uni_nft_shocked(token_id, shocks).await;
+                }
+                value
+            }
+            _ => self.value_gem_shocked(ilk, &ink[0], shocks).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // feed values for usdc (6 decimals) at 1 ref and weth (18 decimals) at 2000 ref,
+    // ref wads per base unit as rays, the same units value_gem multiplies ink by
+    fn usdc_weth_feeds() -> (U256, U256) {
+        (U256::exp10(39), U256::from(2000) * U256::exp10(27))
+    }
+
+    fn position(tick_lower: i32, tick_upper: i32) -> PositionsData {
+        PositionsData {
+            nonce: U256::zero(),
+            operator: Address::zero(),
+            token0: Address::zero(),
+            token1: Address::zero(),
+            fee: 500,
+            tick_lower,
+            tick_upper,
+            liquidity: 10_u128.pow(15),
+            fee_growth_inside_0_last_x128: U256::zero(),
+            fee_growth_inside_1_last_x128: U256::zero(),
+            tokens_owed_0: 0,
+            tokens_owed_1: 0,
+        }
+    }
+
+    #[test]
+    fn sqrt_price_is_token1_per_token0() {
+        let (usdc, weth) = usdc_weth_feeds();
+        let sqrt_price = sqrt_price_x96(usdc, weth);
+        // one usdc base unit is worth 5e8 wei, sqrt(5e8) * 2^96
+        assert_eq!(
+            sqrt_price,
+            U256::from_dec_str("1771595571142957102961017161607260").unwrap()
+        );
+        // the tick the usdc/weth pools trade at with eth at 2000
+        assert_eq!(univ3::get_tick_at_sqrt_ratio(sqrt_price), 200311);
+    }
+
+    #[test]
+    fn sqrt_price_of_same_decimal_pair() {
+        // token0 at 8 ref and token1 at 2 ref, one token0 buys four token1
+        let units = units::new();
+        let sqrt_price = sqrt_price_x96(units.RAY * 8, units.RAY * 2);
+        assert_eq!(sqrt_price, units.X96 * 2);
+        assert_eq!(univ3::get_tick_at_sqrt_ratio(sqrt_price), 13863);
+        // swapping the prices flips the pool to the reciprocal
+        assert_eq!(sqrt_price_x96(units.RAY * 2, units.RAY * 8), units.X96 / 2);
+    }
+
+    #[test]
+    fn in_range_position_holds_both_tokens() {
+        let (usdc, weth) = usdc_weth_feeds();
+        let position = position(199_000, 201_600);
+        let principal = position.principal(sqrt_price_x96(usdc, weth));
+        assert!(!principal.0.is_zero() && !principal.1.is_zero());
+        let value = value_position(&position, (U256::zero(), U256::zero()), usdc, weth, units::new().RAY);
+        assert_eq!(value, (principal.0 * usdc + principal.1 * weth) / units::new().RAY);
+    }
+}
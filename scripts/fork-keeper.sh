@@ -0,0 +1,65 @@
+#!/usr/bin/env bash
+# Runs `ricoterm keeper --dry-run` against a local anvil fork of arbitrum and fails if
+# the keeper can't process blocks, e.g. because the gas feed or vat reads revert.
+#
+# needs anvil and cast (foundry) on PATH and a ./term.toml with a [keeper] section,
+# run from the repo root:
+#
+#   ARB_RPC_URL=https://arb1.arbitrum.io/rpc scripts/fork-keeper.sh
+#
+# FORK_BLOCK pins the fork (latest if unset), BLOCKS is how many blocks to mine while
+# the keeper runs (default 5), PORT is anvil's port (default 8545).
+set -euo pipefail
+
+: "${ARB_RPC_URL:?set ARB_RPC_URL to an arbitrum rpc to fork from}"
+PORT="${PORT:-8545}"
+BLOCKS="${BLOCKS:-5}"
+RPC="http://127.0.0.1:${PORT}"
+LOG="$(mktemp)"
+
+fork_args=(--fork-url "$ARB_RPC_URL" --port "$PORT" --silent)
+if [[ -n "${FORK_BLOCK:-}" ]]; then
+    fork_args+=(--fork-block-number "$FORK_BLOCK")
+fi
+
+cleanup() {
+    kill "${keeper_pid:-}" "${anvil_pid:-}" 2>/dev/null || true
+}
+trap cleanup EXIT
+
+anvil "${fork_args[@]}" &
+anvil_pid=$!
+until cast block-number --rpc-url "$RPC" >/dev/null 2>&1; do
+    sleep 1
+done
+
+cargo build --release --bin ricoterm
+./target/release/ricoterm keeper --rpc "$RPC" --dry-run >"$LOG" 2>&1 &
+keeper_pid=$!
+
+for _ in $(seq "$BLOCKS"); do
+    sleep 3
+    cast rpc anvil_mine 1 --rpc-url "$RPC" >/dev/null
+done
+sleep 3
+
+cat "$LOG"
+if ! kill -0 "$keeper_pid" 2>/dev/null; then
+    echo "keeper exited" >&2
+    exit 1
+fi
+# every block the keeper handles either logs nothing or a line per urn, errors are
+# logged as "block <n>: <error>" without any of the keeper's own wording
+if ! grep -q "^.* keeping " "$LOG"; then
+    echo "keeper didn't start" >&2
+    exit 1
+fi
+if grep -Ev "keeping |is sunk|would bail|bail limit|over limit" "$LOG" | grep -q "block [0-9]*: "; then
+    echo "keeper logged errors on the fork" >&2
+    exit 1
+fi
+if grep -q "failed to get block number" "$LOG"; then
+    echo "keeper couldn't reach the fork" >&2
+    exit 1
+fi
+echo "keeper ran cleanly over ${BLOCKS} blocks"
@@ -0,0 +1,177 @@
+use std::sync::Arc;
+
+use ethers::prelude::*;
+use ricolib::ddso::{
+    gem::Gem,
+    nfpm::NPFM,
+    vat::{uninft_dink, Vat},
+};
+
+use crate::config::TermConfig;
+
+pub type SignerClient = SignerMiddleware<Arc<Provider<Http>>, LocalWallet>;
+
+pub async fn load_signer(
+    config: &TermConfig,
+    provider: &Arc<Provider<Http>>,
+) -> Result<Option<Arc<SignerClient>>, Box<dyn std::error::Error>> {
+    let signer_config = match &config.signer {
+        Some(signer_config) => signer_config,
+        None => return Ok(None),
+    };
+    let wallet = match (&signer_config.keystore, &signer_config.private_key_env) {
+        (Some(keystore), _) => crate::keys::decrypt(keystore)?,
+        (None, Some(private_key_env)) => std::env::var(private_key_env)?.parse::<LocalWallet>()?,
+        (None, None) => return Err("[signer] needs either keystore or private_key_env".into()),
+    };
+    let chain_id = provider.get_chainid().await?;
+    let wallet = wallet.with_chain_id(chain_id.as_u64());
+    Ok(Some(Arc::new(SignerMiddleware::new(provider.clone(), wallet))))
+}
+
+#[derive(Clone, Debug)]
+pub enum Action {
+    ApproveNfts,
+    Deposit(Vec<U256>),
+    Withdraw(Vec<U256>),
+    Collect(U256),
+    // minimums are the previewed amounts less the configured slippage
+    DecreaseLiquidity {
+        token_id: U256,
+        liquidity: u128,
+        amount0_min: U256,
+        amount1_min: U256,
+    },
+    IncreaseLiquidity {
+        token_id: U256,
+        token0: Address,
+        token1: Address,
+        amount0: U256,
+        amount1: U256,
+        amount0_min: U256,
+        amount1_min: U256,
+    },
+}
+
+pub struct Actor {
+    pub signer: Arc<SignerClient>,
+    pub diamond: Address,
+    pub npfm: Address,
+}
+
+impl Actor {
+    // runs the action to completion and returns a one-line status for the ui
+    pub async fn execute(&self, action: Action) -> String {
+        let vat = Vat::new(&self.signer, self.diamond);
+        let npfm = NPFM::new(&self.signer, self.npfm);
+        let usr = self.signer.address();
+        let (label, result) = match action {
+            Action::ApproveNfts => (
+                String::from("approve"),
+                npfm.set_approval_for_all(self.diamond, true).await,
+            ),
+            Action::Deposit(token_ids) => (
+                format!("deposit {}", join_ids(&token_ids)),
+                async {
+                    // the hook pulls the positions, so approve the diamond for all of them
+                    // unless it already is or each one is approved on its own
+                    let mut approved = npfm.is_approved_for_all(usr, self.diamond).await;
+                    if !approved {
+                        approved = true;
+                        for token_id in token_ids.iter() {
+                            if npfm.get_approved(*token_id).await != self.diamond {
+                                approved = false;
+                                break;
+                            }
+                        }
+                    }
+                    if !approved {
+                        match npfm.set_approval_for_all(self.diamond, true).await? {
+                            Some(receipt) if receipt.status == Some(U64::one()) => {}
+                            // surface the failed approval instead of a frob that can't pass
+                            receipt => return Ok(receipt),
+                        }
+                    }
+                    vat.frob(":uninft", usr, uninft_dink(true, &token_ids), I256::zero())
+                        .await
+                }
+                .await,
+            ),
+            Action::Withdraw(token_ids) => (
+                format!("withdraw {}", join_ids(&token_ids)),
+                vat.frob(":uninft", usr, uninft_dink(false, &token_ids), I256::zero())
+                    .await,
+            ),
+            Action::Collect(token_id) => (
+                format!("collect {}", token_id),
+                npfm.collect(token_id, usr).await,
+            ),
+            Action::DecreaseLiquidity {
+                token_id,
+                liquidity,
+                amount0_min,
+                amount1_min,
+            } => (
+                format!("decrease {} by {}", token_id, liquidity),
+                npfm.decrease_liquidity(
+                    token_id,
+                    liquidity,
+                    (amount0_min, amount1_min),
+                    usr,
+                    deadline(),
+                )
+                .await,
+            ),
+            Action::IncreaseLiquidity {
+                token_id,
+                token0,
+                token1,
+                amount0,
+                amount1,
+                amount0_min,
+                amount1_min,
+            } => (
+                format!("increase {}", token_id),
+                async {
+                    // the position manager pulls both tokens, so top up allowances first
+                    for (token, amount) in [(token0, amount0), (token1, amount1)] {
+                        let gem = Gem::new(&self.signer, token);
+                        if gem.allowance(usr, npfm.address).await < amount {
+                            gem.approve(npfm.address, amount).await?;
+                        }
+                    }
+                    npfm.increase_liquidity(
+                        token_id,
+                        (amount0, amount1),
+                        (amount0_min, amount1_min),
+                        deadline(),
+                    )
+                    .await
+                }
+                .await,
+            ),
+        };
+        match result {
+            Ok(Some(receipt)) => match receipt.status {
+                Some(status) if status == U64::one() => {
+                    format!("{} mined in {:?}", label, receipt.transaction_hash)
+                }
+                _ => format!("{} reverted in {:?}", label, receipt.transaction_hash),
+            },
+            Ok(None) => format!("{} dropped from mempool", label),
+            Err(e) => format!("{} failed: {}", label, e),
+        }
+    }
+}
+
+fn deadline() -> U256 {
+    U256::from(chrono::Utc::now().timestamp() + 600)
+}
+
+fn join_ids(token_ids: &[U256]) -> String {
+    token_ids
+        .iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
@@ -8,11 +8,123 @@ pub struct TermConfig {
     pub urns: UrnsConfig,
     pub rico: Rico,
     pub ilks: IlkConfig,
+    pub signer: Option<SignerConfig>,
+    pub keeper: Option<KeeperConfig>,
+    pub poker: Option<PokerConfig>,
+    pub watchlist: Option<Vec<WatchConfig>>,
 }
 
+// extra addresses to cycle the urn panel through, alongside [urns]
+#[derive(Deserialize, Debug)]
+pub struct WatchConfig {
+    pub label: String,
+    pub address: String,
+    pub ilks: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct PokerConfig {
+    // poke at least this often
+    pub interval_seconds: u64,
+    // or sooner when the projected way moves by this many apr percentage points
+    pub min_way_change: f64,
+    pub max_gas_price_gwei: u64,
+    pub poll_millis: u64,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct KeeperConfig {
+    // minimum profit in ref, net of gas, for a bail to be sent
+    pub min_profit: f64,
+    pub max_gas_price_gwei: u64,
+    pub max_bails_per_block: usize,
+    // gas assumed for a bail when it can't be estimated (e.g. --dry-run without a signer)
+    pub bail_gas: u64,
+    pub poll_millis: u64,
+    // ilks to keep, all of them if unset
+    pub ilks: Option<Vec<String>>,
+    // feed pricing the chain's gas token (eth) in ref, the weth ilk's feed if unset
+    pub gas_feed: Option<FeedConfig>,
+}
+
+// a feedbase feed, tag as the string it's packed from, e.g. "weth:ref"
+#[derive(Deserialize, Debug)]
+pub struct FeedConfig {
+    pub src: String,
+    pub tag: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SignerConfig {
+    pub keystore: Option<String>,
+    pub private_key_env: Option<String>,
+    // percent the amounts of a liquidity change may fall short of the preview, 0.5 if unset
+    pub slippage: Option<f64>,
+}
+
+impl SignerConfig {
+    pub fn slippage(&self) -> f64 {
+        self.slippage.unwrap_or(0.5)
+    }
+}
+
+// keys the tui binds globally, never handed out to discovered ilks
+pub const GLOBAL_KEYS: &str = "qcpsmfzotikvgyxjeDO";
+
 #[derive(Deserialize, Debug)]
 pub struct IlkConfig {
     pub key_mappings: HashMap<char, String>,
+    // look up every ilk the vat has initialized at startup, on unless set to false
+    pub discover: Option<bool>,
+}
+
+impl IlkConfig {
+    pub fn discover(&self) -> bool {
+        self.discover.unwrap_or(true)
+    }
+
+    // a mapping on a global key would shadow that key's view, the tui checks mappings first
+    pub fn check_keys(&self) -> Result<(), String> {
+        let mut taken = self
+            .key_mappings
+            .keys()
+            .filter(|key| GLOBAL_KEYS.contains(**key))
+            .map(|key| format!("'{}'", key))
+            .collect::<Vec<String>>();
+        match taken.is_empty() {
+            true => Ok(()),
+            false => {
+                taken.sort();
+                Err(format!(
+                    "[ilks.key_mappings] {} already bound globally, keys {} are reserved",
+                    taken.join(", "),
+                    GLOBAL_KEYS
+                ))
+            }
+        }
+    }
+
+    // configured mappings win, every other ilk gets the first free letter of its name,
+    // then the first free letter or digit overall
+    pub fn assign_keys(&self, ilks: &[String]) -> HashMap<char, String> {
+        let mut key_mappings = self.key_mappings.clone();
+        for ilk in ilks {
+            if key_mappings.values().any(|mapped| mapped == ilk) {
+                continue;
+            }
+            let key = ilk
+                .chars()
+                .filter(|c| c.is_ascii_alphanumeric())
+                .map(|c| c.to_ascii_lowercase())
+                .chain('a'..='z')
+                .chain('0'..='9')
+                .find(|c| !GLOBAL_KEYS.contains(*c) && !key_mappings.contains_key(c));
+            if let Some(key) = key {
+                key_mappings.insert(key, ilk.clone());
+            }
+        }
+        key_mappings
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -25,7 +137,10 @@ pub struct RpcConfig {
 pub struct UrnsConfig {
     pub user_address: String,
     pub user_nickname: Option<String>,
+    #[serde(default)]
     pub ilks: Vec<String>,
+    // scan every known ilk instead of `ilks`, showing only the ones with ink or art
+    pub auto_detect: Option<bool>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -43,5 +158,34 @@ pub fn read_config<T: AsRef<Path>>(path: T) -> Result<TermConfig, Box<dyn std::e
     file.read_to_string(&mut contents)?;
 
     let config: TermConfig = toml::from_str(&contents)?;
+    config.ilks.check_keys()?;
     Ok(config)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ilk_config(mappings: &[(char, &str)]) -> IlkConfig {
+        IlkConfig {
+            key_mappings: mappings.iter().map(|(key, ilk)| (*key, ilk.to_string())).collect(),
+            discover: None,
+        }
+    }
+
+    #[test]
+    fn global_keys_are_reserved() {
+        assert!(ilk_config(&[('w', "weth"), ('u', "usdc")]).check_keys().is_ok());
+        let err = ilk_config(&[('w', "weth"), ('e', "reth"), ('D', "dai")])
+            .check_keys()
+            .unwrap_err();
+        assert!(err.contains("'D', 'e'"), "{}", err);
+    }
+
+    #[test]
+    fn discovered_ilks_skip_global_keys() {
+        let keys = ilk_config(&[]).assign_keys(&["eth".to_string()]);
+        // 'e' and 't' are global, 'h' is the first free letter
+        assert_eq!(keys.get(&'h').map(String::as_str), Some("eth"));
+    }
+}
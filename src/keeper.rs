@@ -0,0 +1,197 @@
+use std::{collections::HashMap, sync::Arc};
+
+use ethers::prelude::*;
+use ricolib::{
+    auction::{estimate_bail, AuctionParams, SUNK},
+    ddso::{
+        feedbase::Feedbase,
+        vat::{Ilk, Vat},
+    },
+    math::units,
+    urns::UrnIndex,
+    utils::string_to_bytes32,
+};
+
+use crate::{
+    actions::{self, SignerClient},
+    config::{KeeperConfig, TermConfig},
+};
+
+pub fn log(message: String) {
+    println!("{} {}", chrono::Utc::now().format("%Y-%m-%d %H:%M:%S"), message);
+}
+
+pub fn flag_value(args: &[String], flag: &str) -> Option<String> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|index| args.get(index + 1).cloned())
+}
+
+struct Keeper {
+    provider: Arc<Provider<Http>>,
+    signer: Option<Arc<SignerClient>>,
+    vat: Vat<Provider<Http>>,
+    feedbase: Feedbase<Provider<Http>>,
+    // src and tag of the feed gas is priced with
+    gas_feed: (Address, H256),
+    config: KeeperConfig,
+    dry_run: bool,
+    urns: UrnIndex,
+}
+
+// the configured gas feed, or the feed the vat prices weth with
+async fn gas_feed(
+    config: &KeeperConfig,
+    vat: &Vat<Provider<Http>>,
+) -> Result<(Address, H256), Box<dyn std::error::Error>> {
+    if let Some(feed) = &config.gas_feed {
+        return Ok((feed.src.parse()?, string_to_bytes32(&feed.tag)));
+    }
+    let missing = |_| "no weth ilk to price gas with, set [keeper] gas_feed";
+    let src = vat.try_geth("weth", "src", Vec::new()).await.map_err(missing)?;
+    let tag = vat.try_geth("weth", "tag", Vec::new()).await.map_err(missing)?;
+    Ok((Address::from_slice(&src.as_bytes()[0..20]), tag))
+}
+
+impl Keeper {
+    // ref price of one wad of the gas token
+    async fn eth_price(&self) -> Result<U256, ContractError<Provider<Http>>> {
+        let (src, tag) = self.gas_feed;
+        Ok(U256::from_big_endian(self.feedbase.try_pull(src, tag).await?.0.as_bytes()))
+    }
+
+    async fn bail_gas(&self, ilk: &str, usr: Address) -> U256 {
+        match &self.signer {
+            Some(signer) => Vat::new(signer, self.vat.address)
+                .estimate_bail_gas(ilk, usr)
+                .await
+                .unwrap_or(U256::from(self.config.bail_gas)),
+            None => U256::from(self.config.bail_gas),
+        }
+    }
+
+    async fn keep(&mut self, block: U64) -> Result<(), Box<dyn std::error::Error>> {
+        let units = units::new();
+        self.urns
+            .sync(self.provider.as_ref(), self.vat.address, block)
+            .await?;
+        let gas_price = self.provider.get_gas_price().await?;
+        if gas_price > U256::from(self.config.max_gas_price_gwei) * units.BLN {
+            log(format!(
+                "block {}: gas price {} gwei over limit, skipping",
+                block,
+                gas_price / units.BLN
+            ));
+            return Ok(());
+        }
+        let par = self.vat.par().await;
+        let eth_price = self.eth_price().await?;
+        let min_profit = U256::from((self.config.min_profit * units.BLN_F64) as u64) * units.BLN;
+        let mut ilks = HashMap::<String, (Ilk, AuctionParams)>::new();
+        let mut bails = 0;
+        let urns = self
+            .urns
+            .open()
+            .filter(|urn| !urn.art.is_zero())
+            .filter(|urn| match &self.config.ilks {
+                Some(ilks) => ilks.contains(&urn.ilk),
+                None => true,
+            })
+            .map(|urn| (urn.ilk.clone(), urn.usr, urn.art))
+            .collect::<Vec<(String, Address, U256)>>();
+        for (ilk, usr, art) in urns {
+            let (spot, deal, tot) = self.vat.safe(&ilk, usr).await;
+            if spot != U256::from(SUNK) {
+                continue;
+            }
+            if !ilks.contains_key(&ilk) {
+                let ilk_data = self.vat.ilks(&ilk).await;
+                let params = AuctionParams::fetch(&self.vat, &ilk).await;
+                ilks.insert(ilk.clone(), (ilk_data, params));
+            }
+            let (ilk_data, params) = &ilks[&ilk];
+            let estimate = estimate_bail(ilk_data, art, par, deal, tot, params);
+            let gas_cost = self.bail_gas(&ilk, usr).await * gas_price * eth_price / units.RAY;
+            let profit = estimate.profit - I256::from_raw(gas_cost);
+            log(format!(
+                "block {}: {} {:?} is sunk, bill {} earn {} gas {} -> profit {}",
+                block,
+                ilk,
+                usr,
+                ethers::utils::format_units(estimate.bill, 18)?,
+                ethers::utils::format_units(estimate.earn, 18)?,
+                ethers::utils::format_units(gas_cost, 18)?,
+                ethers::utils::format_units(profit, 18)?,
+            ));
+            if profit < I256::from_raw(min_profit) {
+                continue;
+            }
+            if bails >= self.config.max_bails_per_block {
+                log(format!("block {}: bail limit reached", block));
+                break;
+            }
+            bails += 1;
+            match (&self.signer, self.dry_run) {
+                (Some(signer), false) => {
+                    let status = match Vat::new(signer, self.vat.address).bail(&ilk, usr).await {
+                        Ok(Some(receipt)) => format!("mined in {:?}", receipt.transaction_hash),
+                        Ok(None) => "dropped from mempool".to_string(),
+                        Err(e) => format!("failed: {}", e),
+                    };
+                    log(format!("block {}: bail {} {:?} {}", block, ilk, usr, status));
+                }
+                _ => log(format!("block {}: would bail {} {:?}", block, ilk, usr)),
+            }
+        }
+        Ok(())
+    }
+}
+
+// `ricoterm keeper [--rpc <url>] [--dry-run]`, --rpc points it at e.g. a local fork
+pub async fn run(config: TermConfig, args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let rpc = flag_value(args, "--rpc").unwrap_or(config.rpc.arb_rpc_url.clone());
+    let dry_run = args.iter().any(|arg| arg == "--dry-run");
+    let provider = Arc::new(Provider::<Http>::try_from(rpc.as_str())?);
+    let signer = actions::load_signer(&config, &provider).await?;
+    if signer.is_none() && !dry_run {
+        return Err("keeper mode needs a [signer] unless run with --dry-run".into());
+    }
+    let keeper_config = config
+        .keeper
+        .ok_or("keeper mode needs a [keeper] section in term.toml")?;
+    let vat = Vat::new(&provider, config.rico.diamond.parse()?);
+    let gas_feed = gas_feed(&keeper_config, &vat).await?;
+    let mut keeper = Keeper {
+        vat,
+        gas_feed,
+        feedbase: Feedbase::new(&provider, config.rico.feedbase.parse()?),
+        provider,
+        signer,
+        config: keeper_config,
+        dry_run,
+        urns: UrnIndex::new(),
+    };
+    log(format!(
+        "keeping {} via {}{}",
+        keeper.vat.address,
+        rpc,
+        match dry_run {
+            true => " (dry run)",
+            false => "",
+        }
+    ));
+    let mut last_block = U64::zero();
+    loop {
+        match keeper.provider.get_block_number().await {
+            Ok(block) if block > last_block => {
+                if let Err(e) = keeper.keep(block).await {
+                    log(format!("block {}: {}", block, e));
+                }
+                last_block = block;
+            }
+            Ok(_) => {}
+            Err(e) => log(format!("failed to get block number: {}", e)),
+        }
+        tokio::time::sleep(tokio::time::Duration::from_millis(keeper.config.poll_millis)).await;
+    }
+}
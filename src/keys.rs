@@ -0,0 +1,264 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use crossterm::{
+    event::{self, KeyCode, KeyModifiers},
+    terminal::{disable_raw_mode, enable_raw_mode},
+};
+use ethers::{
+    core::rand::thread_rng,
+    signers::{coins_bip39::English, LocalWallet, MnemonicBuilder, Signer},
+};
+
+const USAGE: &str = "usage: ricoterm keys <create|import|list|delete> [name] [--mnemonic]";
+
+// keystores live in <config dir>/ricoterm/keystores, one encrypted json per name
+pub fn keystore_dir() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("ricoterm")
+        .join("keystores")
+}
+
+pub fn keystore_path(name: &str) -> PathBuf {
+    keystore_dir().join(name)
+}
+
+// the keystore dir is only readable by its owner, created that way or tightened if it exists
+fn create_keystore_dir() -> io::Result<()> {
+    let dir = keystore_dir();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+        fs::DirBuilder::new().recursive(true).mode(0o700).create(&dir)?;
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o700))?;
+    }
+    #[cfg(not(unix))]
+    fs::create_dir_all(&dir)?;
+    Ok(())
+}
+
+// ethers writes keystores with the process umask, narrow them to the owner
+fn restrict_keystore(name: &str) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(keystore_path(name), fs::Permissions::from_mode(0o600))?;
+    }
+    #[cfg(not(unix))]
+    let _ = name;
+    Ok(())
+}
+
+// names become file names, keep them from escaping the keystore dir or passing for a flag
+fn check_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.starts_with('-') {
+        return Err(format!("{} is not a keystore name", name));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err("keystore names may only contain letters, digits, '-' and '_'".to_string());
+    }
+    Ok(())
+}
+
+// reads a line from the terminal without echoing it
+pub fn prompt_hidden(prompt: &str) -> Result<String, Box<dyn std::error::Error>> {
+    print!("{}", prompt);
+    io::stdout().flush()?;
+    enable_raw_mode()?;
+    let mut input = String::new();
+    let result = loop {
+        match event::read() {
+            Ok(event::Event::Key(key)) => match key.code {
+                // raw mode swallows the interrupt, treat it like the shell would
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    break Err("interrupted")
+                }
+                KeyCode::Enter => break Ok(()),
+                KeyCode::Esc => break Err("aborted"),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            },
+            Ok(_) => {}
+            Err(_) => break Err("failed to read from terminal"),
+        }
+    };
+    disable_raw_mode()?;
+    println!();
+    result?;
+    Ok(input)
+}
+
+fn prompt(prompt: &str) -> Result<String, Box<dyn std::error::Error>> {
+    print!("{}", prompt);
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(input.trim().to_string())
+}
+
+fn prompt_new_passphrase() -> Result<String, Box<dyn std::error::Error>> {
+    let passphrase = prompt_hidden("passphrase: ")?;
+    if passphrase != prompt_hidden("confirm passphrase: ")? {
+        return Err("passphrases do not match".into());
+    }
+    Ok(passphrase)
+}
+
+pub fn decrypt(name: &str) -> Result<LocalWallet, Box<dyn std::error::Error>> {
+    let path = keystore_path(name);
+    if !path.exists() {
+        return Err(format!("no keystore named {} in {}", name, keystore_dir().display()).into());
+    }
+    let passphrase = prompt_hidden(&format!("passphrase for keystore {}: ", name))?;
+    Ok(LocalWallet::decrypt_keystore(path, passphrase)?)
+}
+
+fn create(name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    if keystore_path(name).exists() {
+        return Err(format!("keystore {} already exists", name).into());
+    }
+    let passphrase = prompt_new_passphrase()?;
+    let (wallet, _) =
+        LocalWallet::new_keystore(keystore_dir(), &mut thread_rng(), passphrase, Some(name))?;
+    restrict_keystore(name)?;
+    println!("created {} ({:?})", name, wallet.address());
+    Ok(())
+}
+
+fn import(name: &str, mnemonic: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if keystore_path(name).exists() {
+        return Err(format!("keystore {} already exists", name).into());
+    }
+    let wallet: LocalWallet = match mnemonic {
+        true => MnemonicBuilder::<English>::default()
+            .phrase(prompt_hidden("mnemonic: ")?.as_str())
+            .index(prompt("account index [0]: ")?.parse::<u32>().unwrap_or(0))?
+            .build()?,
+        false => prompt_hidden("private key: ")?
+            .trim_start_matches("0x")
+            .parse()?,
+    };
+    let passphrase = prompt_new_passphrase()?;
+    LocalWallet::encrypt_keystore(
+        keystore_dir(),
+        &mut thread_rng(),
+        wallet.signer().to_bytes(),
+        passphrase,
+        Some(name),
+    )?;
+    restrict_keystore(name)?;
+    println!("imported {} ({:?})", name, wallet.address());
+    Ok(())
+}
+
+fn list() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = keystore_dir();
+    if !dir.exists() {
+        println!("no keystores in {}", dir.display());
+        return Ok(());
+    }
+    let mut names = fs::read_dir(&dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect::<Vec<String>>();
+    names.sort();
+    println!("{}:", dir.display());
+    for name in names {
+        println!("  {}", name);
+    }
+    Ok(())
+}
+
+fn delete(name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let path = keystore_path(name);
+    if !path.exists() {
+        return Err(format!("no keystore named {}", name).into());
+    }
+    match prompt(&format!("delete keystore {}? this cannot be undone [y/N]: ", name))?.as_str() {
+        "y" | "Y" => {
+            fs::remove_file(path)?;
+            println!("deleted {}", name);
+        }
+        _ => println!("kept {}", name),
+    }
+    Ok(())
+}
+
+// `<command> [name] [--mnemonic]`, flags may come anywhere after the command
+struct Args<'a> {
+    command: Option<&'a str>,
+    name: Option<&'a str>,
+    mnemonic: bool,
+}
+
+fn parse_args(args: &[String]) -> Result<Args<'_>, String> {
+    let mut parsed = Args {
+        command: args.first().map(String::as_str),
+        name: None,
+        mnemonic: false,
+    };
+    for arg in args.iter().skip(1).map(String::as_str) {
+        match arg {
+            "--mnemonic" => parsed.mnemonic = true,
+            _ if arg.starts_with("--") => return Err(format!("unknown flag {}\n{}", arg, USAGE)),
+            _ if parsed.name.is_none() => {
+                check_name(arg)?;
+                parsed.name = Some(arg);
+            }
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    Ok(parsed)
+}
+
+pub fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let args = parse_args(args)?;
+    create_keystore_dir()?;
+    match (args.command, args.name) {
+        (Some("create"), Some(name)) => create(name),
+        (Some("import"), Some(name)) => import(name, args.mnemonic),
+        (Some("list"), _) => list(),
+        (Some("delete"), Some(name)) => delete(name),
+        _ => Err(USAGE.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn flags_are_not_names() {
+        let input = args(&["import", "--mnemonic", "main"]);
+        let parsed = parse_args(&input).unwrap();
+        assert_eq!((parsed.command, parsed.name, parsed.mnemonic), (Some("import"), Some("main"), true));
+        let input = args(&["import", "--mnemonic"]);
+        let parsed = parse_args(&input).unwrap();
+        assert_eq!((parsed.name, parsed.mnemonic), (None, true));
+        assert!(parse_args(&args(&["import", "main", "--mnemonc"])).is_err());
+        assert!(parse_args(&args(&["import", "main", "other"])).is_err());
+    }
+
+    #[test]
+    fn names_stay_in_the_keystore_dir() {
+        assert!(check_name("main_2").is_ok());
+        assert!(check_name("hot-wallet").is_ok());
+        for name in ["", "-m", "../main", "keys/main", "a\\b", "."] {
+            assert!(check_name(name).is_err(), "{} accepted", name);
+        }
+    }
+}
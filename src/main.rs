@@ -1,4 +1,8 @@
+mod actions;
 mod config;
+mod keeper;
+mod keys;
+mod poker;
 mod monet;
 mod urn;
 use chrono::NaiveDateTime;
@@ -11,15 +15,19 @@ use ethers::prelude::*;
 use ricolib::{
     ddso::{
         events::{Palms, TryIntoPalms, NEW_PALM_0_SIG, NEW_PALM_2_SIG},
-        feedbase::Feedbase,
+        feedbase::{is_err_ttl, Feedbase},
         gem::Gem,
         nfpm::NPFM,
         uniwrapper::UniWrapper,
         vat::*,
         vox::*,
     },
+    ilks::{discover_ilks, discover_uni_tokens, GemCache},
     math::units,
+    stress::{stress_urn, Shocks, StressedUrn},
+    urns::UrnIndex,
     utils::string_to_bytes32,
+    validation::{check_frob, Frob},
     valuation::Valuer,
 };
 use std::{
@@ -27,6 +35,7 @@ use std::{
     io,
     sync::{mpsc, Arc, Mutex},
     thread,
+    time::{Duration, Instant},
 };
 use tui::{
     backend::CrosstermBackend,
@@ -35,11 +44,18 @@ use tui::{
     widgets::{Block, Borders, Paragraph},
     Terminal,
 };
-use urn::UrnData;
+use urn::{
+    FeedRead, FrobQuery, IlkRow, IlkUtilization, LeaderboardRow, NftRow, OracleFeed,
+    PositionPreview, Protocol, RangeSeries, UniIlkDetail, UniNftFlow, UniTokenRow, UrnData,
+    WatchTotal, Watched, WhatIf,
+};
 
 use tui::style::Modifier;
 
+#[allow(clippy::too_many_arguments)]
 async fn fetch_all_urn_data_for_ilk<T: Middleware + Clone>(
+    provider: &Arc<Provider<Http>>,
+    gems: &GemCache,
     ilk: &str,
     vat: &Vat<T>,
     feedbase: &Feedbase<T>,
@@ -72,11 +88,7 @@ async fn fetch_all_urn_data_for_ilk<T: Middleware + Clone>(
     };
     let art: U256 = vat.urns(ilk, wallet_address).await;
     let ililk: Ilk = vat.ilks(ilk).await;
-    let seconds_since_last_drip: i64 = (chrono::Utc::now().naive_utc()
-        - NaiveDateTime::from_timestamp_opt(ililk.rho.as_u128() as i64, 0).unwrap())
-    .num_seconds();
-    let syn_rack =
-        (0..seconds_since_last_drip).fold(ililk.rack, |acc, _| acc * ililk.fee / units.RAY);
+    let syn_rack = drip_rack(&ililk);
     let loan = art * syn_rack * par / units.RAY / units.RAY;
     let value = match ilk {
         ":uninft" => ink,
@@ -89,6 +101,16 @@ async fn fetch_all_urn_data_for_ilk<T: Middleware + Clone>(
     };
 
     let debt = art * syn_rack * units.BLN / units.RAY / units.BLN;
+    let gem = gems.get(provider, vat, ilk).await;
+    let (wallet, wallet_nfts) = match &gem {
+        Some(gem) => (
+            Gem::new(provider, gem.address)
+                .balance_of(wallet_address)
+                .await,
+            None,
+        ),
+        None => (U256::zero(), Some(npfm.tokens_of_owner(wallet_address).await)),
+    };
 
     UrnData {
         ink_name: String::from(ilk),
@@ -99,6 +121,344 @@ async fn fetch_all_urn_data_for_ilk<T: Middleware + Clone>(
         value,
         safety,
         ninks,
+        rack: syn_rack,
+        par,
+        dust: ililk.dust,
+        line: ililk.line,
+        tart: ililk.tart,
+        fee: ililk.fee,
+        gem,
+        wallet,
+        wallet_nfts,
+    }
+}
+
+async fn fetch_uninft_flow<T: Middleware + Clone>(
+    provider: &Arc<Provider<Http>>,
+    world: &RicoWorld<T>,
+    wallet_address: Address,
+    nft_cursor: usize,
+) -> UniNftFlow {
+    let mut flow = UniNftFlow {
+        wallet: world.npfm.tokens_of_owner(wallet_address).await,
+        deposited: world.vat.ink(":uninft", wallet_address).await,
+        approved: world
+            .npfm
+            .is_approved_for_all(wallet_address, world.vat.address)
+            .await,
+        preview: None,
+    };
+    // only positions still in the wallet can be collected from or resized
+    if let Some((token_id, false)) = flow.token_at(nft_cursor) {
+        let valuer = Valuer::new(&world.npfm, &world.vat, &world.feedbase, &world.uniwrapper);
+        let position = world.npfm.positions(token_id).await;
+        flow.preview = Some(PositionPreview {
+            token_id,
+            token0: position.token0,
+            token1: position.token1,
+            decimals0: Gem::new(provider, position.token0).decimals().await.as_u32(),
+            decimals1: Gem::new(provider, position.token1).decimals().await.as_u32(),
+            liquidity: position.liquidity,
+            total: valuer.uni_nft_amounts(&token_id).await,
+            fees: world.npfm.collect_preview(token_id, wallet_address).await,
+        });
+    }
+    flow
+}
+
+// every position deposited in the :uninft urn, with token metadata for display
+async fn fetch_nft_rows<T: Middleware + Clone>(
+    provider: &Arc<Provider<Http>>,
+    world: &RicoWorld<T>,
+    wallet_address: Address,
+) -> Vec<NftRow> {
+    let valuer = Valuer::new(&world.npfm, &world.vat, &world.feedbase, &world.uniwrapper);
+    let mut rows = Vec::<NftRow>::new();
+    for token_id in world.vat.ink(":uninft", wallet_address).await {
+        let breakdown = valuer.uni_nft_breakdown(&token_id).await;
+        let gem0 = Gem::new(provider, breakdown.position.token0);
+        let gem1 = Gem::new(provider, breakdown.position.token1);
+        rows.push(NftRow {
+            symbol0: gem0.symbol().await,
+            symbol1: gem1.symbol().await,
+            decimals0: gem0.decimals().await.as_u32(),
+            decimals1: gem1.decimals().await.as_u32(),
+            breakdown,
+        });
+    }
+    rows
+}
+
+// feed and liqr of every token the :uninft hook supports, plus the tokens of each deposited
+// position in case one was configured without a NewPalm2. Read failures are kept in the
+// detail so the rest of it still shows.
+async fn fetch_uni_ilk_detail<T: Middleware + Clone>(
+    provider: &Arc<Provider<Http>>,
+    world: &RicoWorld<T>,
+    wallet_address: Address,
+) -> UniIlkDetail {
+    let valuer = Valuer::new(&world.npfm, &world.vat, &world.feedbase, &world.uniwrapper);
+    let mut errors = Vec::<String>::new();
+    let mut tokens = match discover_uni_tokens(provider.as_ref(), world.vat.address).await {
+        Ok(tokens) => tokens,
+        Err(e) => {
+            errors.push(format!("couldn't discover the hook's tokens: {}", e));
+            Vec::new()
+        }
+    };
+    let token_ids = match world.vat.try_ink(":uninft", wallet_address).await {
+        Ok(token_ids) => token_ids,
+        Err(e) => {
+            errors.push(format!("couldn't read deposited positions: {}", e));
+            Vec::new()
+        }
+    };
+    let mut positions = Vec::<(U256, Address, Address)>::new();
+    for token_id in token_ids {
+        let position = world.npfm.positions(token_id).await;
+        for token in [position.token0, position.token1] {
+            if !tokens.contains(&token) {
+                tokens.push(token);
+            }
+        }
+        positions.push((token_id, position.token0, position.token1));
+    }
+    let mut detail = UniIlkDetail {
+        tokens: Vec::new(),
+        positions,
+        errors,
+    };
+    for token in tokens {
+        let gem = Gem::new(provider, token);
+        detail.tokens.push(UniTokenRow {
+            info: valuer.uni_token(token).await,
+            symbol: gem.symbol().await,
+            decimals: gem.decimals().await.as_u32(),
+        });
+    }
+    detail
+}
+
+// vat totals and every known ilk's debt against its line
+async fn fetch_protocol<T: Middleware + Clone>(
+    world: &RicoWorld<T>,
+    known_ilks: &[String],
+    par: U256,
+) -> Protocol {
+    let mut ilks = Vec::<IlkUtilization>::new();
+    for ilk in known_ilks.iter() {
+        let ilk_data = world.vat.ilks(ilk).await;
+        ilks.push(IlkUtilization {
+            ilk: ilk.clone(),
+            debt: ilk_data.tart * ilk_data.rack,
+            line: ilk_data.line,
+        });
+    }
+    Protocol {
+        debt: world.vat.debt().await,
+        ceil: world.vat.ceil().await,
+        joy: world.vat.joy().await,
+        sin: world.vat.sin().await,
+        rest: world.vat.rest().await,
+        par,
+        ilks,
+    }
+}
+
+// every feed the terminal reads: mar from the vox tip, each known ilk's src/tag (per token
+// for :uninft) and xau:usd
+async fn fetch_oracle_feeds<T: Middleware + Clone>(
+    provider: &Arc<Provider<Http>>,
+    world: &RicoWorld<T>,
+    known_ilks: &[String],
+) -> Vec<OracleFeed> {
+    let (tip_src, tip_tag) = world.vox.tip().await;
+    let mut sources = vec![("mar (vox tip)".to_string(), tip_src, tip_tag)];
+    let mut feeds = Vec::<OracleFeed>::new();
+    for ilk in known_ilks.iter() {
+        if ilk == ":uninft" {
+            let valuer = Valuer::new(&world.npfm, &world.vat, &world.feedbase, &world.uniwrapper);
+            let tokens = match discover_uni_tokens(provider.as_ref(), world.vat.address).await {
+                Ok(tokens) => tokens,
+                Err(e) => {
+                    feeds.push(OracleFeed {
+                        label: ":uninft".to_string(),
+                        src: Address::zero(),
+                        tag: H256::zero(),
+                        read: FeedRead::Unresolved(format!("couldn't discover the hook's tokens: {}", e)),
+                    });
+                    Vec::new()
+                }
+            };
+            for token in tokens {
+                let (src, tag, _) = valuer.uni_token_info(token).await;
+                let symbol = Gem::new(provider, token).symbol().await;
+                sources.push((format!(":uninft {}", symbol), src, tag));
+            }
+            continue;
+        }
+        let src = Address::from_slice(
+            &world.vat.geth::<H256>(ilk, "src", Vec::new()).await.as_bytes()[..20],
+        );
+        let tag = world.vat.geth::<H256>(ilk, "tag", Vec::new()).await;
+        sources.push((ilk.clone(), src, tag));
+    }
+    sources.push((
+        "xau:usd".to_string(),
+        world.chainlink_address,
+        string_to_bytes32("xau:usd"),
+    ));
+    for (label, src, tag) in sources {
+        let read = match world.feedbase.try_pull(src, tag).await {
+            Ok((val, ttl)) => FeedRead::Value {
+                val: U256::from_big_endian(val.as_bytes()),
+                ttl,
+            },
+            Err(e) if is_err_ttl(&e) => FeedRead::ErrTtl,
+            Err(e) => FeedRead::Failed(e.to_string()),
+        };
+        feeds.push(OracleFeed {
+            label,
+            src,
+            tag,
+            read,
+        });
+    }
+    feeds
+}
+
+// an unchanged :uninft urn is revalued this often on the leaderboard, as its prices move
+const UNI_URN_REVALUE_SECS: u64 = 60;
+// the :uninft tvl values every position in the diamond, so it's only recomputed this often
+// and the ilk table shows its age
+const UNI_TVL_REFRESH_SECS: u64 = 60;
+
+// number of positions deposited in :uninft, their value before liqr and when it was computed
+async fn fetch_uni_tvl<T: Middleware + Clone>(world: &RicoWorld<T>) -> (usize, U256, Instant) {
+    if let Some((refreshed, positions, tvl)) = *world.uni_tvl.lock().unwrap() {
+        if refreshed.elapsed() < Duration::from_secs(UNI_TVL_REFRESH_SECS) {
+            return (positions, tvl, refreshed);
+        }
+    }
+    let units = units::new();
+    // the hook keeps every deposited position in the diamond
+    let valuer = Valuer::new(&world.npfm, &world.vat, &world.feedbase, &world.uniwrapper);
+    let token_ids = world.npfm.tokens_of_owner(world.vat.address).await;
+    let mut tvl = U256::zero();
+    for token_id in token_ids.iter() {
+        let breakdown = valuer.uni_nft_breakdown(token_id).await;
+        tvl += breakdown.value * breakdown.liqr / units.RAY;
+    }
+    let refreshed = Instant::now();
+    *world.uni_tvl.lock().unwrap() = Some((refreshed, token_ids.len(), tvl));
+    (token_ids.len(), tvl, refreshed)
+}
+
+// rack with the fee accrued since the ilk was last dripped
+fn drip_rack(ilk: &Ilk) -> U256 {
+    let units = units::new();
+    let seconds_since_last_drip: i64 = (chrono::Utc::now().naive_utc()
+        - NaiveDateTime::from_timestamp_opt(ilk.rho.as_u128() as i64, 0).unwrap())
+    .num_seconds();
+    (0..seconds_since_last_drip).fold(ilk.rack, |acc, _| acc * ilk.fee / units.RAY)
+}
+
+// ranks every open urn in the index as of `block`. Gem urns are valued from the indexed
+// ink with one feed read per ilk, only :uninft urns read their positions and those values
+// are reused until the urn changes or they're UNI_URN_REVALUE_SECS old.
+async fn fetch_leaderboard<T: Middleware + Clone>(
+    provider: &Arc<Provider<Http>>,
+    world: &RicoWorld<T>,
+    block: U64,
+) -> Result<Vec<LeaderboardRow>, Box<dyn std::error::Error>> {
+    let units = units::new();
+    let valuer = Valuer::new(&world.npfm, &world.vat, &world.feedbase, &world.uniwrapper);
+    let mut index = { world.urn_index.lock().unwrap().clone() };
+    index
+        .sync(provider.as_ref(), world.vat.address, block)
+        .await?;
+    let mut uni_values = { world.uni_urn_values.lock().unwrap().clone() };
+    let par = world.vat.par().await;
+    let mut rows = Vec::<LeaderboardRow>::new();
+    for ilk in index.ilks() {
+        let rack = drip_rack(&world.vat.ilks(&ilk).await);
+        let feed = match ilk.as_str() {
+            ":uninft" => None,
+            _ => Some(valuer.gem_feed(&ilk, &Shocks::default()).await),
+        };
+        for urn in index.by_ilk(&ilk).filter(|urn| urn.is_open()) {
+            let mut valued = None;
+            let mut error = None;
+            let (ink, value) = match feed {
+                Some((feed, liqr)) => (urn.ink, feed * urn.ink / liqr),
+                None => {
+                    let cached = uni_values.get(&urn.usr).copied().filter(|(block_number, at, _, _)| {
+                        *block_number == urn.block_number
+                            && at.elapsed() < Duration::from_secs(UNI_URN_REVALUE_SECS)
+                    });
+                    match cached {
+                        Some((_, at, positions, value)) => {
+                            valued = Some(at);
+                            (positions, value)
+                        }
+                        None => match world.vat.try_ink(&ilk, urn.usr).await {
+                            Ok(token_ids) => {
+                                let mut value = U256::zero();
+                                for token_id in token_ids.iter() {
+                                    value += valuer.value_uni_nft(token_id).await;
+                                }
+                                let positions = U256::from(token_ids.len());
+                                let at = Instant::now();
+                                uni_values.insert(urn.usr, (urn.block_number, at, positions, value));
+                                valued = Some(at);
+                                (positions, value)
+                            }
+                            Err(e) => {
+                                error = Some(e.to_string());
+                                (U256::zero(), U256::zero())
+                            }
+                        },
+                    }
+                }
+            };
+            rows.push(LeaderboardRow {
+                usr: urn.usr,
+                ilk: ilk.clone(),
+                ink,
+                art: urn.art,
+                rack,
+                value,
+                loan: urn.art * rack * par / units.RAY / units.RAY,
+                valued,
+                error,
+            });
+        }
+    }
+    *world.urn_index.lock().unwrap() = index;
+    *world.uni_urn_values.lock().unwrap() = uni_values;
+    Ok(rows)
+}
+
+// checks a what-if frob against the urn's current `ink` and art
+async fn fetch_what_if<T: Middleware + Clone>(
+    world: &RicoWorld<T>,
+    query: FrobQuery,
+    ink: U256,
+    usr: Address,
+) -> WhatIf {
+    let ilk = world.vat.ilks(&query.ilk).await;
+    let frob = Frob {
+        ink,
+        art: world.vat.urns(&query.ilk, usr).await,
+        dink: query.dink,
+        dart: query.dart,
+    };
+    let violations = check_frob(&ilk, &frob, world.vat.debt().await, world.vat.ceil().await);
+    WhatIf {
+        decimals: world.gems.decimals(&query.ilk),
+        query,
+        frob,
+        violations,
     }
 }
 
@@ -109,7 +469,7 @@ async fn fetch_data<T: Middleware + Clone>(
     state: &Arc<Mutex<State>>,
 ) -> Result<ChainData, Box<dyn std::error::Error>> {
     let mut urn_data = Vec::<UrnData>::new();
-    let (urns, active_ilks, wallet_address, active_view, active_palm_2) = {
+    let (urns, active_ilks, wallet_address, active_view, active_palm_2, nft_cursor, frob_query) = {
         let state = state.lock().unwrap();
         (
             state.urns.clone(),
@@ -117,21 +477,46 @@ async fn fetch_data<T: Middleware + Clone>(
             state.user_address,
             state.selected_active_view,
             state.active_new_palm_2,
+            state.nft_cursor,
+            state.frob_query.clone(),
+        )
+    };
+    let (watchlist, watching_all, scanning, shocks, known_ilks) = {
+        let state = state.lock().unwrap();
+        (
+            state.watchlist.clone(),
+            state.watching_all(),
+            state.scanning(),
+            state.shocks.clone(),
+            state.known_ilks.clone(),
         )
     };
 
-    for ilk in urns {
-        urn_data.push(
-            fetch_all_urn_data_for_ilk(
-                &ilk,
-                &world.vat,
-                &world.feedbase,
-                &world.npfm,
-                &world.uniwrapper,
-                wallet_address,
-            )
-            .await,
-        );
+    // the all-watched view only draws watch_totals, which read every watched urn below
+    if !watching_all {
+        for ilk in urns {
+            urn_data.push(
+                fetch_all_urn_data_for_ilk(
+                    &provider,
+                    &world.gems,
+                    &ilk,
+                    &world.vat,
+                    &world.feedbase,
+                    &world.npfm,
+                    &world.uniwrapper,
+                    wallet_address,
+                )
+                .await,
+            );
+        }
+    }
+    // scanned addresses are checked against every known ilk, only keep the ones they use
+    let mut empty_ilks = Vec::<String>::new();
+    if scanning {
+        let (open, empty): (Vec<UrnData>, Vec<UrnData>) =
+            urn_data.into_iter().partition(UrnData::has_position);
+        urn_data = open;
+        empty_ilks = empty.into_iter().map(|urn| urn.ink_name).collect();
     }
     // // get current block number from the provider
     let par = world.vat.par().await;
@@ -148,30 +533,48 @@ async fn fetch_data<T: Middleware + Clone>(
     let last_refreshed_as_time =
         chrono::NaiveDateTime::from_timestamp_opt(last_refreshed.as_u64() as i64, 0).unwrap();
 
-    let mut ilk_data = Vec::<Ilk>::new();
+    let units = units::new();
+    let mut ilk_data = Vec::<IlkRow>::new();
     for ilk in active_ilks.iter() {
         let mut ilk_info = world.vat.ilks(ilk.as_str()).await;
         if ilk == ":uninft" {
-            ilk_data.push(ilk_info);
+            let (positions, tvl, refreshed) = fetch_uni_tvl(&world).await;
+            ilk_info.tink = Some(U256::from(positions));
+            ilk_info.inkd = Some(U256::zero());
+            ilk_data.push(IlkRow {
+                name: ilk.clone(),
+                ilk: ilk_info,
+                liqr: None,
+                feed: None,
+                tvl,
+                tvl_valued: Some(refreshed),
+            });
             continue;
         }
-        let gem = Gem::new(
-            &provider,
-            H160::from_slice(
-                &world
-                    .vat
-                    .geth::<H256>(ilk.as_str(), "gem", Vec::new())
-                    .await
-                    .as_bytes()[..20],
-            ),
+        let liqr: U256 = world.vat.geth::<RU256>(ilk, "liqr", Vec::new()).await.into();
+        let src = Address::from_slice(
+            &world.vat.geth::<H256>(ilk, "src", Vec::new()).await.as_bytes()[..20],
         );
-        ilk_info.tink = Some(gem.balance_of(world.vat.address).await);
-        ilk_info.inkd = Some(gem.decimals().await);
-        ilk_data.push(ilk_info);
+        let tag = world.vat.geth::<H256>(ilk, "tag", Vec::new()).await;
+        let feed = U256::from_big_endian(world.feedbase.pull(src, tag).await.0.as_bytes());
+        if let Some(meta) = world.gems.get(&provider, &world.vat, ilk).await {
+            let gem = Gem::new(&provider, meta.address);
+            ilk_info.tink = Some(gem.balance_of(world.vat.address).await);
+            ilk_info.inkd = Some(U256::from(meta.decimals));
+        }
+        ilk_data.push(IlkRow {
+            name: ilk.clone(),
+            tvl: ilk_info.tink.unwrap_or_default() * feed / units.RAY,
+            ilk: ilk_info,
+            liqr: Some(liqr),
+            feed: Some(feed),
+            tvl_valued: None,
+        });
     }
     let way = world.vox.way().await;
     let tau = world.vox.tau().await;
     let how = world.vox.how().await;
+    let cap = world.vox.cap().await;
     let xau = U256::from_big_endian(
         world
             .feedbase
@@ -181,6 +584,109 @@ async fn fetch_data<T: Middleware + Clone>(
             .as_bytes(),
     );
 
+    let uninft_flow = match active_view {
+        SelectedActiveView::UniNft => {
+            Some(fetch_uninft_flow(&provider, &world, wallet_address, nft_cursor).await)
+        }
+        _ => None,
+    };
+
+    let nft_rows = match active_view {
+        SelectedActiveView::NftBreakdown | SelectedActiveView::RangeChart => {
+            fetch_nft_rows(&provider, &world, wallet_address).await
+        }
+        _ => Vec::new(),
+    };
+
+    let uni_ilk_detail = match active_view == SelectedActiveView::Ilk
+        && active_ilks.iter().any(|ilk| ilk == ":uninft")
+    {
+        true => Some(fetch_uni_ilk_detail(&provider, &world, wallet_address).await),
+        false => None,
+    };
+
+    let protocol = match active_view {
+        SelectedActiveView::Protocol => Some(fetch_protocol(&world, &known_ilks, par).await),
+        _ => None,
+    };
+
+    let oracle_feeds = match active_view {
+        SelectedActiveView::Oracles => fetch_oracle_feeds(&provider, &world, &known_ilks).await,
+        _ => Vec::new(),
+    };
+
+    let what_if = match (active_view, frob_query) {
+        (SelectedActiveView::WhatIf, Some(query)) => {
+            let ink = match query.ilk.as_str() {
+                ":uninft" => Ok(U256::zero()),
+                _ => world
+                    .vat
+                    .try_ink(&query.ilk, wallet_address)
+                    .await
+                    .map(|ink| ink.first().copied().unwrap_or_default()),
+            };
+            match ink {
+                Ok(ink) => Some(fetch_what_if(&world, query, ink, wallet_address).await),
+                Err(e) => {
+                    let mut state = state.lock().unwrap();
+                    state.input_error = Some(format!("{}: can't read ink: {}", query.ilk, e));
+                    state.frob_query = None;
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
+
+    let stress = match (active_view, shocks) {
+        (SelectedActiveView::Stress, Some(shocks)) => {
+            let valuer = Valuer::new(&world.npfm, &world.vat, &world.feedbase, &world.uniwrapper);
+            let mut stress = Vec::<StressedUrn>::new();
+            for urn in urn_data.iter() {
+                stress.push(
+                    stress_urn(&valuer, &urn.ink_name, wallet_address, urn.loan, &shocks).await,
+                );
+            }
+            stress
+        }
+        _ => Vec::new(),
+    };
+
+    let leaderboard = match active_view {
+        SelectedActiveView::Leaderboard => fetch_leaderboard(&provider, &world, block).await?,
+        _ => Vec::new(),
+    };
+
+    let watch_totals = match watching_all {
+        true => {
+            let mut totals = Vec::<WatchTotal>::new();
+            for watched in watchlist.iter() {
+                let mut total = WatchTotal {
+                    label: watched.label.clone(),
+                    ..Default::default()
+                };
+                for ilk in watched.ilks.iter() {
+                    let urn = fetch_all_urn_data_for_ilk(
+                        &provider,
+                        &world.gems,
+                        ilk,
+                        &world.vat,
+                        &world.feedbase,
+                        &world.npfm,
+                        &world.uniwrapper,
+                        watched.address,
+                    )
+                    .await;
+                    total.value += urn.value;
+                    total.loan += urn.loan;
+                }
+                totals.push(total);
+            }
+            totals
+        }
+        false => Vec::new(),
+    };
+
     let mut logs = match active_view {
         SelectedActiveView::NewPalm2 => {
             let filter = Filter::new()
@@ -204,11 +710,12 @@ async fn fetch_data<T: Middleware + Clone>(
         _ => Vec::new(),
     };
 
-    logs.sort_by(|a, b| a.block_number.cmp(&b.block_number));
+    logs.sort_by_key(|log| log.block_number);
     logs.reverse();
 
     Ok(ChainData {
         urn_data,
+        empty_ilks,
         par,
         mar,
         block,
@@ -217,27 +724,49 @@ async fn fetch_data<T: Middleware + Clone>(
         way,
         tau,
         how,
+        cap,
         xau,
         logs: logs
             .into_iter()
             .map(|log| log.try_into_palms().unwrap())
             .collect(),
+        uninft_flow,
+        nft_rows,
+        uni_ilk_detail,
+        protocol,
+        oracle_feeds,
+        what_if,
+        stress,
+        leaderboard,
+        watch_totals,
     })
 }
 
 #[derive(Clone)]
 pub struct ChainData {
     pub urn_data: Vec<UrnData>,
+    // scanned ilks without ink or art, collapsed out of urn_data
+    pub empty_ilks: Vec<String>,
     pub par: U256,
     pub mar: U256,
     pub block: U64,
     pub last_refreshed: NaiveDateTime,
-    pub ilks: Vec<Ilk>,
+    pub ilks: Vec<IlkRow>,
     pub way: U256,
     pub tau: U256,
     pub how: U256,
+    pub cap: U256,
     pub xau: U256,
     pub logs: Vec<Palms>,
+    pub uninft_flow: Option<UniNftFlow>,
+    pub nft_rows: Vec<NftRow>,
+    pub uni_ilk_detail: Option<UniIlkDetail>,
+    pub protocol: Option<Protocol>,
+    pub oracle_feeds: Vec<OracleFeed>,
+    pub what_if: Option<WhatIf>,
+    pub stress: Vec<StressedUrn>,
+    pub leaderboard: Vec<LeaderboardRow>,
+    pub watch_totals: Vec<WatchTotal>,
 }
 
 pub struct RicoWorld<T: Middleware + Clone> {
@@ -247,6 +776,13 @@ pub struct RicoWorld<T: Middleware + Clone> {
     npfm: NPFM<T>,
     uniwrapper: UniWrapper<T>,
     chainlink_address: Address,
+    urn_index: Mutex<UrnIndex>,
+    // :uninft urn values on the leaderboard by usr, with the index block_number of the urn
+    // they were computed for, when, and the urn's position count
+    uni_urn_values: Mutex<HashMap<Address, (U64, Instant, U256, U256)>>,
+    // when the :uninft tvl was last computed, with the position count and tvl
+    uni_tvl: Mutex<Option<(Instant, usize, U256)>>,
+    gems: Arc<GemCache>,
 }
 
 #[derive(Clone)]
@@ -259,9 +795,91 @@ pub struct State {
     pub selected_active_view: SelectedActiveView,
     pub selected_market_view: SelectedMarketView,
     pub menu_index: i32,
+    pub nft_cursor: usize,
+    pub nft_selected: Vec<U256>,
+    pub tx_status: Option<String>,
+    pub liquidity_pct: u8,
+    // from [signer] slippage, applied to the minimums of liquidity changes
+    pub slippage: f64,
+    // position charted in the range view, wraps around the deposited positions
+    pub chart_index: usize,
+    pub target_safety: f64,
+    pub input_buffer: String,
+    pub frob_query: Option<FrobQuery>,
+    pub shocks: Option<Shocks>,
+    pub leaderboard_sort: LeaderboardSort,
+    pub ilk_sort: IlkSort,
+    pub watchlist: Vec<Watched>,
+    // index into watchlist, one past the end shows the totals of every address
+    pub watch_index: usize,
+    // address entered at runtime, shown instead of the watchlist until cleared
+    pub inspecting: Option<Address>,
+    // every configured or discovered ilk, scanned for positions when inspecting or auto-detecting
+    pub known_ilks: Vec<String>,
+    // scan known_ilks for every watched address too, from [urns] auto_detect
+    pub auto_detect: bool,
+    pub input_error: Option<String>,
+    // gem metadata shared with the fetch thread, warmed for known_ilks at startup
+    pub gems: Arc<GemCache>,
 }
 
 impl State {
+    pub fn watching_all(&self) -> bool {
+        self.inspecting.is_none() && self.watch_index == self.watchlist.len()
+    }
+
+    // whether urn_data covers every known ilk and has to be narrowed to open urns
+    pub fn scanning(&self) -> bool {
+        self.auto_detect || self.inspecting.is_some()
+    }
+
+    pub fn watched_label(&self) -> String {
+        if let Some(address) = self.inspecting {
+            return format!("{:?}", address);
+        }
+        match self.watchlist.get(self.watch_index) {
+            Some(watched) => watched.label.clone(),
+            None => String::from("all watched"),
+        }
+    }
+
+    // moves the urn panel to the next watched address, then to the totals of all of them
+    pub fn cycle_watchlist(&mut self) {
+        match self.inspecting.take() {
+            // leaving an inspected address goes back to the watched one it replaced
+            Some(_) => {}
+            None => self.watch_index = (self.watch_index + 1) % (self.watchlist.len() + 1),
+        }
+        if let Some(watched) = self.watchlist.get(self.watch_index) {
+            self.user_address = watched.address;
+            self.urns = watched.ilks.clone();
+        }
+    }
+
+    // switches the urn panel to an address typed into the inspect prompt,
+    // an empty prompt goes back to the watchlist
+    pub fn inspect(&mut self) {
+        let input = self.input_buffer.trim();
+        if input.is_empty() {
+            if self.inspecting.is_some() {
+                self.cycle_watchlist();
+            }
+            self.selected_active_view = SelectedActiveView::Clear;
+            return;
+        }
+        match input.parse::<Address>() {
+            Ok(address) => {
+                self.inspecting = Some(address);
+                self.user_address = address;
+                self.urns = self.known_ilks.clone();
+                self.input_buffer.clear();
+                self.input_error = None;
+                self.selected_active_view = SelectedActiveView::Clear;
+            }
+            Err(e) => self.input_error = Some(format!("{}: {}", input, e)),
+        }
+    }
+
     pub fn handle_non_ilk_key_press(&mut self, keycode: &KeyCode) {
         match keycode {
             KeyCode::Char('s') => {
@@ -301,6 +919,116 @@ impl State {
                 }
                 _ => {}
             },
+            KeyCode::Char('o') => match self.selected_active_view {
+                SelectedActiveView::Clear => {
+                    self.selected_active_view = SelectedActiveView::UniNft;
+                    self.nft_cursor = 0;
+                    self.nft_selected.clear();
+                }
+                SelectedActiveView::UniNft => {
+                    self.selected_active_view = SelectedActiveView::Clear;
+                }
+                _ => {}
+            },
+            KeyCode::Char('i') if self.selected_active_view == SelectedActiveView::Clear => {
+                self.selected_active_view = SelectedActiveView::WhatIf;
+                self.input_buffer.clear();
+                self.frob_query = None;
+            }
+            KeyCode::Char('x') if self.selected_active_view == SelectedActiveView::Clear => {
+                self.selected_active_view = SelectedActiveView::Stress;
+                self.input_buffer.clear();
+                self.input_error = None;
+                self.shocks = None;
+            }
+            KeyCode::Char('g') if self.selected_active_view == SelectedActiveView::Clear => {
+                self.selected_active_view = SelectedActiveView::Inspect;
+                self.input_buffer.clear();
+                self.input_error = None;
+            }
+            KeyCode::Char('k') => match self.selected_active_view {
+                SelectedActiveView::Clear => {
+                    self.selected_active_view = SelectedActiveView::Leaderboard;
+                }
+                SelectedActiveView::Leaderboard => {
+                    self.selected_active_view = SelectedActiveView::Clear;
+                }
+                _ => {}
+            },
+            KeyCode::Tab if self.selected_active_view == SelectedActiveView::Leaderboard => {
+                self.leaderboard_sort = match self.leaderboard_sort {
+                    LeaderboardSort::Safety => LeaderboardSort::Debt,
+                    LeaderboardSort::Debt => LeaderboardSort::Value,
+                    LeaderboardSort::Value => LeaderboardSort::Safety,
+                };
+            }
+            KeyCode::Tab if self.selected_active_view == SelectedActiveView::Ilk => {
+                self.ilk_sort = self.ilk_sort.next();
+            }
+            KeyCode::Char('j') => match self.selected_active_view {
+                SelectedActiveView::Clear => {
+                    self.selected_active_view = SelectedActiveView::NftBreakdown;
+                }
+                SelectedActiveView::NftBreakdown => {
+                    self.selected_active_view = SelectedActiveView::Clear;
+                }
+                _ => {}
+            },
+            KeyCode::Char('e') => match self.selected_active_view {
+                SelectedActiveView::Clear => {
+                    self.selected_active_view = SelectedActiveView::RangeChart;
+                    self.chart_index = 0;
+                }
+                SelectedActiveView::RangeChart => {
+                    self.selected_active_view = SelectedActiveView::Clear;
+                }
+                _ => {}
+            },
+            KeyCode::Tab if self.selected_active_view == SelectedActiveView::RangeChart => {
+                self.chart_index += 1;
+            }
+            KeyCode::Char('y') => match self.selected_active_view {
+                SelectedActiveView::Clear => {
+                    self.selected_active_view = SelectedActiveView::Portfolio;
+                }
+                SelectedActiveView::Portfolio => {
+                    self.selected_active_view = SelectedActiveView::Clear;
+                }
+                _ => {}
+            },
+            KeyCode::Char('D') => match self.selected_active_view {
+                SelectedActiveView::Clear => {
+                    self.selected_active_view = SelectedActiveView::Protocol;
+                }
+                SelectedActiveView::Protocol => {
+                    self.selected_active_view = SelectedActiveView::Clear;
+                }
+                _ => {}
+            },
+            KeyCode::Char('O') => match self.selected_active_view {
+                SelectedActiveView::Clear => {
+                    self.selected_active_view = SelectedActiveView::Oracles;
+                }
+                SelectedActiveView::Oracles => {
+                    self.selected_active_view = SelectedActiveView::Clear;
+                }
+                _ => {}
+            },
+            KeyCode::Char('t') => match self.selected_active_view {
+                SelectedActiveView::Clear => {
+                    self.selected_active_view = SelectedActiveView::Planner;
+                }
+                SelectedActiveView::Planner => {
+                    self.selected_active_view = SelectedActiveView::Clear;
+                }
+                _ => {}
+            },
+            KeyCode::Left if self.selected_active_view == SelectedActiveView::Planner => {
+                self.target_safety = f64::max(self.target_safety - 0.1, 1.0);
+            }
+            KeyCode::Right if self.selected_active_view == SelectedActiveView::Planner => {
+                self.target_safety += 0.1;
+            }
             KeyCode::Char('z') => match self.selected_active_view {
                 SelectedActiveView::Clear => {
                     self.selected_active_view = SelectedActiveView::NewPalm0;
@@ -313,15 +1041,237 @@ impl State {
             _ => {}
         }
     }
+
+    // the what-if, stress and inspect prompts own the keyboard until they are closed with esc
+    pub fn handle_input_key_press(&mut self, keycode: &KeyCode) {
+        match keycode {
+            KeyCode::Char(c) => self.input_buffer.push(*c),
+            KeyCode::Backspace => {
+                self.input_buffer.pop();
+            }
+            KeyCode::Enter => match self.selected_active_view {
+                SelectedActiveView::Inspect => self.inspect(),
+                SelectedActiveView::Stress => match Shocks::parse(&self.input_buffer) {
+                    Ok(shocks) => {
+                        self.shocks = Some(shocks);
+                        self.input_error = None;
+                    }
+                    Err(e) => self.input_error = Some(e),
+                },
+                _ => match FrobQuery::parse(&self.input_buffer, &self.known_ilks, &self.gems) {
+                    Ok(query) => {
+                        self.frob_query = Some(query);
+                        self.input_error = None;
+                    }
+                    Err(e) => {
+                        self.frob_query = None;
+                        self.input_error = Some(e);
+                    }
+                },
+            },
+            KeyCode::Esc => {
+                self.selected_active_view = SelectedActiveView::Clear;
+                self.input_buffer.clear();
+                self.input_error = None;
+                self.frob_query = None;
+                self.shocks = None;
+            }
+            _ => {}
+        }
+    }
+
+    // keys in the uninft view move the cursor, toggle selection and build transactions
+    pub fn handle_uninft_key_press(
+        &mut self,
+        keycode: &KeyCode,
+        flow: Option<&UniNftFlow>,
+    ) -> Option<actions::Action> {
+        let flow = flow?;
+        match keycode {
+            KeyCode::Down => {
+                if self.nft_cursor + 1 < flow.len() {
+                    self.nft_cursor += 1;
+                }
+                None
+            }
+            KeyCode::Up => {
+                self.nft_cursor = self.nft_cursor.saturating_sub(1);
+                None
+            }
+            KeyCode::Char(' ') => {
+                if let Some((token_id, _)) = flow.token_at(self.nft_cursor) {
+                    match self.nft_selected.iter().position(|id| *id == token_id) {
+                        Some(index) => {
+                            self.nft_selected.remove(index);
+                        }
+                        None => self.nft_selected.push(token_id),
+                    }
+                }
+                None
+            }
+            KeyCode::Char('a') => Some(actions::Action::ApproveNfts),
+            KeyCode::Char('[') => {
+                self.liquidity_pct = std::cmp::max(self.liquidity_pct, 50) - 25;
+                None
+            }
+            KeyCode::Char(']') => {
+                self.liquidity_pct = std::cmp::min(self.liquidity_pct, 75) + 25;
+                None
+            }
+            KeyCode::Char('f') => flow
+                .preview
+                .as_ref()
+                .map(|preview| actions::Action::Collect(preview.token_id)),
+            KeyCode::Char('-') => flow.preview.as_ref().map(|preview| {
+                let (amount0_min, amount1_min) =
+                    preview.decrease_min(self.liquidity_pct, self.slippage);
+                actions::Action::DecreaseLiquidity {
+                    token_id: preview.token_id,
+                    liquidity: preview.liquidity * self.liquidity_pct as u128 / 100,
+                    amount0_min,
+                    amount1_min,
+                }
+            }),
+            KeyCode::Char('+') => flow.preview.as_ref().map(|preview| {
+                let (amount0, amount1) = preview.increase(self.liquidity_pct);
+                let (amount0_min, amount1_min) =
+                    preview.increase_min(self.liquidity_pct, self.slippage);
+                actions::Action::IncreaseLiquidity {
+                    token_id: preview.token_id,
+                    token0: preview.token0,
+                    token1: preview.token1,
+                    amount0,
+                    amount1,
+                    amount0_min,
+                    amount1_min,
+                }
+            }),
+            KeyCode::Char('d') => {
+                let token_ids = self
+                    .nft_selected
+                    .iter()
+                    .filter(|id| flow.wallet.contains(id))
+                    .copied()
+                    .collect::<Vec<U256>>();
+                match token_ids.is_empty() {
+                    true => None,
+                    false => Some(actions::Action::Deposit(token_ids)),
+                }
+            }
+            KeyCode::Char('w') => {
+                let token_ids = self
+                    .nft_selected
+                    .iter()
+                    .filter(|id| flow.deposited.contains(id))
+                    .copied()
+                    .collect::<Vec<U256>>();
+                match token_ids.is_empty() {
+                    true => None,
+                    false => Some(actions::Action::Withdraw(token_ids)),
+                }
+            }
+            _ => None,
+        }
+    }
 }
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = std::env::args().collect::<Vec<String>>();
+    if args.get(1).map(String::as_str) == Some("keys") {
+        return keys::run(&args[2..]);
+    }
     let config = config::read_config("./term.toml")?;
-    let ilk_help_message = format!("{:?}", &config.ilks.key_mappings);
+    if args.get(1).map(String::as_str) == Some("keeper") {
+        return keeper::run(config, &args[2..]).await;
+    }
+    if args.get(1).map(String::as_str) == Some("poker") {
+        return poker::run(config, &args[2..]).await;
+    }
+    let provider = Arc::new(Provider::<Http>::try_from(config.rpc.arb_rpc_url.as_str())?);
+    let discovered = match config.ilks.discover() {
+        true => discover_ilks(&provider, &Vat::new(&provider, config.rico.diamond.parse()?))
+            .await
+            .unwrap_or_else(|e| {
+                eprintln!("failed to discover ilks: {}", e);
+                Vec::new()
+            }),
+        false => Vec::new(),
+    };
+    let key_mappings = config.ilks.assign_keys(
+        &discovered
+            .iter()
+            .map(|ilk| ilk.ilk.clone())
+            .collect::<Vec<String>>(),
+    );
+    let mut ilk_keys = key_mappings.iter().collect::<Vec<(&char, &String)>>();
+    ilk_keys.sort();
+    let ilk_help_message = ilk_keys
+        .iter()
+        .map(|(key, ilk)| {
+            match discovered
+                .iter()
+                .find(|discovered| &&discovered.ilk == ilk)
+                .and_then(|discovered| discovered.symbol.as_ref())
+            {
+                Some(symbol) if !symbol.is_empty() => format!("{}: {} ({})", key, ilk, symbol),
+                _ => format!("{}: {}", key, ilk),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
     let mut live_ilks_key_char: HashMap<KeyCode, String> = HashMap::new();
-    for (key, value) in config.ilks.key_mappings.clone().into_iter() {
-        live_ilks_key_char.insert(KeyCode::Char(key), value.to_string());
+    for (key, value) in key_mappings.iter() {
+        live_ilks_key_char.insert(KeyCode::Char(*key), value.to_string());
+    }
+    let mut known_ilks = config.urns.ilks.clone();
+    known_ilks.extend(key_mappings.values().cloned());
+    known_ilks.sort();
+    known_ilks.dedup();
+    let auto_detect = config.urns.auto_detect.unwrap_or(false);
+    let urns = match auto_detect {
+        true => known_ilks.clone(),
+        false => config.urns.ilks.clone(),
+    };
+    // typed amounts are parsed on the ui thread, so every known ilk's decimals are resolved up front
+    let gems = Arc::new(GemCache::new());
+    {
+        let vat = Vat::new(&provider, config.rico.diamond.parse()?);
+        for ilk in known_ilks.iter() {
+            gems.get(&provider, &vat, ilk).await;
+        }
+    }
+    let wallet_address: Address = config.urns.user_address.parse()?;
+    let mut watchlist = vec![Watched {
+        label: config
+            .urns
+            .user_nickname
+            .clone()
+            .unwrap_or(config.urns.user_address.clone()),
+        address: wallet_address,
+        ilks: urns.clone(),
+    }];
+    for watch in config.watchlist.iter().flatten() {
+        watchlist.push(Watched {
+            label: watch.label.clone(),
+            address: watch.address.parse()?,
+            ilks: match auto_detect {
+                true => known_ilks.clone(),
+                false => watch.ilks.clone(),
+            },
+        });
     }
+    let actor = actions::load_signer(&config, &provider)
+        .await?
+        .map(|signer| {
+            Ok::<_, Box<dyn std::error::Error>>(Arc::new(actions::Actor {
+                signer,
+                diamond: config.rico.diamond.parse()?,
+                npfm: config.rico.npfm.parse()?,
+            }))
+        })
+        .transpose()?;
+
+    // the keystore passphrase prompt has to happen before the tui takes over the terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen)?;
@@ -329,9 +1279,6 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let provider = Arc::new(Provider::<Http>::try_from(config.rpc.arb_rpc_url.as_str())?);
-    let wallet_address: Address = config.urns.user_address.parse()?;
-
     let world = Arc::new(RicoWorld {
         vat: Vat::new(&provider, config.rico.diamond.parse()?),
         vox: Vox::new(&provider, config.rico.diamond.parse()?),
@@ -339,38 +1286,65 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         npfm: NPFM::new(&provider, config.rico.npfm.parse()?),
         uniwrapper: UniWrapper::new(&provider, config.rico.uniwrapper.parse()?),
         chainlink_address: config.rico.chain_link_feed.parse()?,
+        urn_index: Mutex::new(UrnIndex::new()),
+        uni_urn_values: Mutex::new(HashMap::new()),
+        uni_tvl: Mutex::new(None),
+        gems: gems.clone(),
     });
 
     let state = Arc::new(Mutex::new(State {
         active_ilk: Vec::<String>::new(),
-        urns: config.urns.ilks.clone(),
+        urns: urns.clone(),
         user_address: wallet_address,
         active_new_palm_2: None,
         selected_menu_view: SelectedMenuView::Urn,
         selected_active_view: SelectedActiveView::Clear,
         selected_market_view: SelectedMarketView::MarAndPar,
         menu_index: -1,
+        nft_cursor: 0,
+        nft_selected: Vec::<U256>::new(),
+        tx_status: None,
+        liquidity_pct: 25,
+        slippage: config.signer.as_ref().map_or(0.5, |signer| signer.slippage()),
+        chart_index: 0,
+        target_safety: 1.5,
+        input_buffer: String::new(),
+        frob_query: None,
+        shocks: None,
+        leaderboard_sort: LeaderboardSort::Safety,
+        ilk_sort: IlkSort::Tvl,
+        watchlist,
+        watch_index: 0,
+        inspecting: None,
+        known_ilks,
+        auto_detect,
+        input_error: None,
+        gems,
     }));
 
-    let mut empty_urn_vec = Vec::<UrnData>::new();
-    for urn in config.urns.ilks.iter() {
-        empty_urn_vec.push(UrnData {
-            ink_name: urn.to_string(),
-            ..Default::default()
-        })
-    }
     let data = Arc::new(Mutex::new(ChainData {
-        urn_data: empty_urn_vec,
+        urn_data: UrnData::placeholders(&urns),
+        empty_ilks: Vec::<String>::new(),
         par: U256::zero(),
         mar: U256::zero(),
         block: U64::zero(),
         last_refreshed: chrono::NaiveDateTime::from_timestamp_opt(0, 0).unwrap(),
-        ilks: Vec::<Ilk>::new(),
+        ilks: Vec::<IlkRow>::new(),
         way: U256::zero(),
         tau: U256::zero(),
         how: U256::zero(),
+        cap: U256::zero(),
         xau: U256::zero(),
         logs: Vec::<Palms>::new(),
+        uninft_flow: None,
+        nft_rows: Vec::<NftRow>::new(),
+        uni_ilk_detail: None,
+        protocol: None,
+        oracle_feeds: Vec::<OracleFeed>::new(),
+        what_if: None,
+        stress: Vec::<StressedUrn>::new(),
+        leaderboard: Vec::<LeaderboardRow>::new(),
+        watch_totals: Vec::<WatchTotal>::new(),
     }));
 
     // Spawn background task for fetching data
@@ -407,9 +1381,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             let size = f.size();
             let mut canvas = monet::TermCanvas::init(size);
             // Populate top section with title
-            let title = Paragraph::new(match &config.urns.user_nickname {
-                Some(nickname) => format!("{}'s urns", nickname),
-                None => format!("{}'s urns", &config.urns.user_address),
+            let title = Paragraph::new(match state.watchlist.len() {
+                _ if state.inspecting.is_some() => format!(
+                    "{}'s urns (inspecting, 'v' to go back)",
+                    state.watched_label()
+                ),
+                1 => format!("{}'s urns", state.watched_label()),
+                _ => format!(
+                    "{}'s urns ({}/{}, 'v' to cycle)",
+                    state.watched_label(),
+                    state.watch_index + 1,
+                    state.watchlist.len() + 1
+                ),
             })
             .style(Style::default().add_modifier(Modifier::BOLD));
             f.render_widget(title, canvas.navbar);
@@ -427,6 +1410,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 SelectedMarketView::DollarConversion => {
                     monet::paint_pricing_screen(data.mar, data.par, data.xau)
                 }
+                SelectedMarketView::Poke => monet::paint_poke(
+                    data.mar,
+                    data.par,
+                    data.way,
+                    data.how,
+                    data.cap,
+                    data.tau,
+                    data.last_refreshed,
+                ),
             };
 
             f.render_widget(marpar_paragraph, canvas.right_main_pane.market_view);
@@ -439,18 +1431,38 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     canvas.left_main_panel.menu_view = Some(canvas.left_main_panel.base_view);
                     f.render_widget(menu_paragraph, canvas.left_main_panel.menu_view.unwrap());
                 }
+                SelectedMenuView::Urn if state.watching_all() => {
+                    let totals_paragraph = monet::paint_watch_totals(&data.watch_totals);
+                    f.render_widget(totals_paragraph, canvas.left_main_panel.base_view);
+                }
                 SelectedMenuView::Urn => {
-                    let display_space_per_urn = 100 / config.urns.ilks.len() as u16;
-                    let urn_display_constraints = config
-                        .urns
-                        .ilks
+                    let urn_area = match data.empty_ilks.is_empty() {
+                        true => canvas.left_main_panel.base_view,
+                        false => {
+                            let areas = Layout::default()
+                                .direction(Direction::Vertical)
+                                .constraints([Constraint::Min(0), Constraint::Length(3)])
+                                .split(canvas.left_main_panel.base_view);
+                            f.render_widget(monet::paint_empty_urns(&data.empty_ilks), areas[1]);
+                            areas[0]
+                        }
+                    };
+                    if data.urn_data.is_empty() {
+                        let empty_paragraph = Paragraph::new("No urns with ink or art")
+                            .block(Block::default().borders(Borders::ALL));
+                        f.render_widget(empty_paragraph, urn_area);
+                    }
+                    let display_space_per_urn =
+                        100 / std::cmp::max(data.urn_data.len(), 1) as u16;
+                    let urn_display_constraints = data
+                        .urn_data
                         .iter()
                         .map(|_| Constraint::Percentage(display_space_per_urn))
                         .collect::<Vec<Constraint>>();
                     let urn_views = Layout::default()
                         .direction(Direction::Vertical)
                         .constraints(urn_display_constraints)
-                        .split(canvas.left_main_panel.base_view);
+                        .split(urn_area);
                     canvas.left_main_panel.set_urn_view(urn_views);
                     let urn_views = &canvas.left_main_panel.urn_view.unwrap();
                     for (i, urn) in data.urn_data.iter().enumerate() {
@@ -480,7 +1492,72 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         }
                     })),
             );
-            f.render_widget(active_view_paragraph, canvas.right_main_pane.ilk_view);
+            match (state.selected_active_view, data.nft_rows.is_empty()) {
+                (SelectedActiveView::Ilk, _) if !state.active_ilk.is_empty() && !data.ilks.is_empty() => {
+                    let area = canvas.right_main_pane.ilk_view;
+                    let rows = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([
+                            Constraint::Length(data.ilks.len() as u16 + 3),
+                            Constraint::Min(0),
+                        ])
+                        .split(area);
+                    let table = monet::paint_ilk_table(
+                        &data.ilks,
+                        state.ilk_sort,
+                        data.par,
+                        data.xau,
+                        &canvas.color_map,
+                    )
+                    .block(
+                        Block::default()
+                            .title(monet::ilk_table_title(&data.ilks))
+                            .borders(Borders::ALL)
+                            .border_style(style::Style::default().fg(style::Color::LightYellow)),
+                    );
+                    f.render_widget(table, rows[0]);
+                    f.render_widget(active_view_paragraph, rows[1]);
+                }
+                (SelectedActiveView::Protocol, _) if data.protocol.is_some() => {
+                    let protocol = data.protocol.as_ref().unwrap();
+                    let block = Block::default()
+                        .title("protocol")
+                        .borders(Borders::ALL)
+                        .border_style(style::Style::default().fg(style::Color::LightYellow));
+                    let area = block.inner(canvas.right_main_pane.ilk_view);
+                    f.render_widget(block, canvas.right_main_pane.ilk_view);
+                    let summary = monet::paint_protocol(protocol);
+                    let gauges = monet::paint_utilization_gauges(protocol);
+                    let rows = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints(
+                            std::iter::once(Constraint::Length(4))
+                                .chain(gauges.iter().map(|_| Constraint::Length(1)))
+                                .chain(std::iter::once(Constraint::Min(0)))
+                                .collect::<Vec<Constraint>>(),
+                        )
+                        .split(area);
+                    f.render_widget(summary, rows[0]);
+                    for (gauge, row) in gauges.into_iter().zip(rows.iter().skip(1)) {
+                        f.render_widget(gauge, *row);
+                    }
+                }
+                (SelectedActiveView::RangeChart, false) => {
+                    let row = &data.nft_rows[state.chart_index % data.nft_rows.len()];
+                    let loan = data
+                        .urn_data
+                        .iter()
+                        .find(|urn| urn.ink_name == ":uninft")
+                        .map(|urn| urn.loan)
+                        .unwrap_or_default();
+                    let series = RangeSeries::new(row, &data.nft_rows, loan);
+                    f.render_widget(
+                        monet::paint_range_chart(&series),
+                        canvas.right_main_pane.ilk_view,
+                    );
+                }
+                _ => f.render_widget(active_view_paragraph, canvas.right_main_pane.ilk_view),
+            }
 
             let footer_paragraph =
                 monet::paint_footer(data.block, data.last_refreshed, ilk_help_message.as_str());
@@ -491,19 +1568,75 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             // Data was refreshed
         } else if event::poll(std::time::Duration::from_millis(200))? {
             if let event::Event::Key(key) = event::read()? {
+                let in_input_view = {
+                    matches!(
+                        state.lock().unwrap().selected_active_view,
+                        SelectedActiveView::WhatIf
+                            | SelectedActiveView::Stress
+                            | SelectedActiveView::Inspect
+                    )
+                };
+                if in_input_view {
+                    let mut state = state.lock().unwrap();
+                    let user_address = state.user_address;
+                    state.handle_input_key_press(&key.code);
+                    if state.user_address != user_address {
+                        let mut data = data.lock().unwrap();
+                        data.urn_data = UrnData::placeholders(&state.urns);
+                        data.empty_ilks.clear();
+                    }
+                    continue;
+                }
+                let in_uninft_view = {
+                    state.lock().unwrap().selected_active_view == SelectedActiveView::UniNft
+                };
+                if in_uninft_view
+                    && matches!(
+                        key.code,
+                        KeyCode::Up
+                            | KeyCode::Down
+                            | KeyCode::Char(' ' | 'a' | 'd' | 'w' | 'f' | '[' | ']' | '-' | '+')
+                    )
+                {
+                    let action = {
+                        let flow = data.lock().unwrap().uninft_flow.clone();
+                        state
+                            .lock()
+                            .unwrap()
+                            .handle_uninft_key_press(&key.code, flow.as_ref())
+                    };
+                    if let Some(action) = action {
+                        match &actor {
+                            Some(actor) => {
+                                let actor = Arc::clone(actor);
+                                let state = Arc::clone(&state);
+                                state.lock().unwrap().tx_status =
+                                    Some(format!("{:?} pending...", action));
+                                tokio::spawn(async move {
+                                    let status = actor.execute(action).await;
+                                    let mut state = state.lock().unwrap();
+                                    state.tx_status = Some(status);
+                                    state.nft_selected.clear();
+                                });
+                            }
+                            None => {
+                                state.lock().unwrap().tx_status =
+                                    Some("no [signer] configured".to_string());
+                            }
+                        }
+                    }
+                    continue;
+                }
                 match live_ilks_key_char.get(&key.code).cloned() {
                     Some(ilk) => {
                         let mut state = state.lock().unwrap();
                         match state.selected_active_view {
-                            SelectedActiveView::Ilk => {
-                                if !state.active_ilk.contains(&ilk) {
-                                    state.active_ilk.push(ilk);
-                                }
-                            }
-                            SelectedActiveView::NewPalm2 => {
-                                if !state.active_ilk.contains(&ilk) {
-                                    state.active_ilk.push(ilk);
-                                }
+                            SelectedActiveView::Ilk
+                            | SelectedActiveView::NewPalm2
+                            | SelectedActiveView::Leaderboard
+                                if !state.active_ilk.contains(&ilk) =>
+                            {
+                                state.active_ilk.push(ilk);
                             }
 
                             SelectedActiveView::Clear => {
@@ -526,6 +1659,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                             state.active_new_palm_2 = None;
                             data.lock().unwrap().ilks.clear();
                         }
+                        KeyCode::Char('v') => {
+                            let mut state = state.lock().unwrap();
+                            state.cycle_watchlist();
+                            let mut data = data.lock().unwrap();
+                            data.urn_data = UrnData::placeholders(&state.urns);
+                            data.empty_ilks.clear();
+                            data.watch_totals.clear();
+                        }
                         KeyCode::Char('p') => {
                             let mut state = state.lock().unwrap();
                             state.active_ilk.pop();
@@ -539,6 +1680,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                                         SelectedMarketView::DollarConversion;
                                 }
                                 SelectedMarketView::DollarConversion => {
+                                    state.selected_market_view = SelectedMarketView::Poke;
+                                }
+                                SelectedMarketView::Poke => {
                                     state.selected_market_view = SelectedMarketView::MarAndPar;
                                 }
                             };
@@ -604,6 +1748,7 @@ pub enum SelectedMenuView {
 pub enum SelectedMarketView {
     MarAndPar,
     DollarConversion,
+    Poke,
 }
 
 #[derive(PartialEq, Eq, Clone, Copy)]
@@ -612,5 +1757,60 @@ pub enum SelectedActiveView {
     Settings,
     NewPalm0,
     NewPalm2,
+    UniNft,
+    NftBreakdown,
+    RangeChart,
+    Protocol,
+    Oracles,
+    Planner,
+    Portfolio,
+    WhatIf,
+    Stress,
+    Inspect,
+    Leaderboard,
     Clear,
 }
+
+// ilk table columns, in display order
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum IlkSort {
+    Fee,
+    Rack,
+    Drip,
+    Tart,
+    Tink,
+    Dust,
+    Line,
+    Liqr,
+    Chop,
+    Price,
+    Tvl,
+}
+
+impl IlkSort {
+    pub const ALL: [IlkSort; 11] = [
+        IlkSort::Fee,
+        IlkSort::Rack,
+        IlkSort::Drip,
+        IlkSort::Tart,
+        IlkSort::Tink,
+        IlkSort::Dust,
+        IlkSort::Line,
+        IlkSort::Liqr,
+        IlkSort::Chop,
+        IlkSort::Price,
+        IlkSort::Tvl,
+    ];
+
+    pub fn next(self) -> Self {
+        let index = IlkSort::ALL.iter().position(|sort| *sort == self).unwrap();
+        IlkSort::ALL[(index + 1) % IlkSort::ALL.len()]
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum LeaderboardSort {
+    Safety,
+    Debt,
+    Value,
+}
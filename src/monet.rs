@@ -1,23 +1,33 @@
 use std::collections::HashMap;
 
 use chrono::NaiveDateTime;
-use ethers::types::{H160, U256, U64};
+use ethers::types::{H160, I256, U256, U64};
 use ricolib::{
     ddso::{
         events::{NewPalm0, NewPalm2, Palms},
-        vat::Ilk,
+        nfpm::RangeStatus,
     },
     math::units,
+    poke::{project_poke, way_to_apr},
+    stress::StressedUrn,
     utils::bytes32_to_string,
 };
 use tui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Span, Spans, Text},
-    widgets::{Block, Borders, Paragraph},
+    symbols,
+    widgets::{Axis, Block, Borders, Cell, Chart, Dataset, Gauge, GraphType, Paragraph, Row, Table},
 };
 
-use crate::{config::TermConfig, urn::UrnData, ChainData, SelectedActiveView, State};
+use crate::{
+    config::TermConfig,
+    urn::{
+        fee_apr, feed_price, to_f64, utilization, FeedRead, FeedStatus, IlkRow, LeaderboardRow, OracleFeed, NftRow, Portfolio, Protocol, RangeSeries,
+        UniIlkDetail, UniNftFlow, UrnData, WatchTotal, WhatIf,
+    },
+    ChainData, IlkSort, LeaderboardSort, SelectedActiveView, State,
+};
 
 pub struct RightMainPanel {
     pub market_view: Rect,
@@ -50,6 +60,7 @@ impl LeftMainPanel {
 }
 
 pub struct TermCanvas {
+    #[allow(dead_code)]
     pub size: Rect,
     pub navbar: Rect,
     pub left_main_panel: LeftMainPanel,
@@ -105,27 +116,41 @@ impl TermCanvas {
     }
 }
 
-pub fn paint_urn(urn: &UrnData, is_active: bool) -> Paragraph {
+pub fn paint_urn(urn: &UrnData, is_active: bool) -> Paragraph<'_> {
     let ink = match &urn.ninks {
-        Some(ninks) => ninks
+        Some(ninks) => format!(
+            "{} ('j' for breakdown)",
+            ninks
+                .iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        None => format!(
+            "{} {}",
+            ethers::utils::format_units(urn.ink, urn.ink_decimals()).unwrap(),
+            urn.gem.as_ref().map_or("", |gem| gem.symbol.as_str())
+        ),
+    };
+    let wallet = match &urn.wallet_nfts {
+        Some(nfts) if nfts.is_empty() => "no undeposited positions".to_string(),
+        Some(nfts) => nfts
             .iter()
             .map(|x| x.to_string())
             .collect::<Vec<String>>()
             .join(", "),
-        None => ethers::utils::format_units(
-            urn.ink,
-            match urn.ink_name.as_str() {
-                "usdc" => 6,
-                _ => 18,
-            },
-        )
-        .unwrap(),
+        None => format!(
+            "{} {}",
+            ethers::utils::format_units(urn.wallet, urn.ink_decimals()).unwrap(),
+            urn.gem.as_ref().map_or("", |gem| gem.symbol.as_str())
+        ),
     };
     let urn_text = format!(
-        "art:   {}\ndebt:  {}\n\tink:   {} \nloan/value: {:.12} / {:.12} --> safety: {:.5}",
+        "art:   {}\ndebt:  {}\n\tink:   {} \n\twallet: {}\nloan/value: {:.12} / {:.12} --> safety: {:.5}",
         urn.art.low_u64() as f64 / 10_u64.pow(18) as f64,
         (urn.debt.as_u128() as f64 / 10_u64.pow(18) as f64),
         ink,
+        wallet,
         (urn.loan.as_u128() as f64 / 10_u64.pow(18) as f64),
         (urn.value.as_u128() as f64 / 10_u64.pow(18) as f64),
         urn.safety
@@ -142,39 +167,218 @@ pub fn paint_urn(urn: &UrnData, is_active: bool) -> Paragraph {
     )
 }
 
-pub fn paint_ilk<T: From<String>>(ilk: &Ilk, current_time: NaiveDateTime) -> T {
+// one line for every scanned ilk the address has no ink or art in
+pub fn paint_empty_urns(ilks: &[String]) -> Paragraph<'_> {
+    Paragraph::new(Span::styled(
+        ilks.join(", "),
+        Style::default().fg(Color::DarkGray),
+    ))
+    .block(
+        Block::default()
+            .title("empty")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::DarkGray)),
+    )
+}
+
+pub fn paint_watch_totals(totals: &[WatchTotal]) -> Paragraph<'_> {
     let units = units::new();
-    let time_since_update =
-        current_time - NaiveDateTime::from_timestamp_opt(ilk.rho.as_u128() as i64, 0).unwrap();
-    let time_since_update_string = format!(
-        "{} hours, {} minutes, {} seconds",
-        time_since_update.num_seconds() / 3600,
-        (time_since_update.num_seconds() % 3600) / 60,
-        time_since_update.num_seconds() % 60
-    );
-    format!(
-        "  tart: {}\n  tink: {}\n  rack: {}\n  rho: {} UTC ({} hours ago)\n fee: {}%",
-        (((ilk.tart * units.BLN) / units.WAD).as_u128() as f64 / units.BLN_F64),
-        ethers::utils::format_units(
-            ilk.tink.unwrap_or(U256::zero()),
-            ilk.inkd.unwrap_or(U256::zero()).as_u64() as u32
+    let all = WatchTotal {
+        label: String::from("total"),
+        value: totals.iter().fold(U256::zero(), |acc, total| acc + total.value),
+        loan: totals.iter().fold(U256::zero(), |acc, total| acc + total.loan),
+    };
+    let line = |total: &WatchTotal| {
+        format!(
+            "{:<16} loan/value: {:.6} / {:.6} --> safety: {:.5}",
+            total.label,
+            total.loan.as_u128() as f64 / units.WAD_F64,
+            total.value.as_u128() as f64 / units.WAD_F64,
+            total.safety()
         )
-        .unwrap(),
-        (((ilk.rack * units.BLN) / units.RAY).as_u128() as f64 / units.BLN_F64),
-        NaiveDateTime::from_timestamp_opt(ilk.rho.as_u128() as i64, 0).unwrap(),
-        time_since_update_string,
-        (((ilk.fee * units.WAD) / units.RAY).as_u128() as f64 / units.BLN_F64.powf(2.0) - 1_f64)
-            * units.BANKYEAR
-            * 100.0
+    };
+    let mut lines = totals
+        .iter()
+        .map(|total| Spans::from(Span::raw(line(total))))
+        .collect::<Vec<Spans>>();
+    if totals.is_empty() {
+        lines.push(Spans::from(Span::raw("Awaiting watchlist data...")));
+    } else {
+        lines.push(Spans::from(Span::styled(
+            line(&all),
+            Style::default().add_modifier(Modifier::BOLD),
+        )));
+    }
+    Paragraph::new(lines).block(
+        Block::default()
+            .title("all watched")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::White)),
     )
-    .into()
+}
+
+static ILK_TABLE_WIDTHS: [Constraint; 13] = [
+    Constraint::Length(8),
+    Constraint::Length(8),
+    Constraint::Length(10),
+    Constraint::Length(12),
+    Constraint::Length(12),
+    Constraint::Length(12),
+    Constraint::Length(10),
+    Constraint::Length(12),
+    Constraint::Length(7),
+    Constraint::Length(7),
+    Constraint::Length(12),
+    Constraint::Length(14),
+    Constraint::Length(14),
+];
+
+// value an ilk is sorted by, ilks without the column (:uninft liqr and price) sort last
+fn ilk_column(row: &IlkRow, sort: IlkSort) -> f64 {
+    let ilk = &row.ilk;
+    match sort {
+        IlkSort::Fee => fee_apr(ilk.fee),
+        IlkSort::Rack => to_f64(ilk.rack, 27),
+        // longest since the last drip first
+        IlkSort::Drip => -(ilk.rho.as_u64() as f64),
+        IlkSort::Tart => to_f64(ilk.tart, 18),
+        IlkSort::Tink => to_f64(ilk.tink.unwrap_or_default(), row.decimals()),
+        IlkSort::Dust => to_f64(ilk.dust, 45),
+        IlkSort::Line => to_f64(ilk.line, 45),
+        IlkSort::Liqr => row.liqr.map_or(f64::NEG_INFINITY, |liqr| to_f64(liqr, 27)),
+        IlkSort::Chop => to_f64(ilk.chop, 27),
+        IlkSort::Price => row.price().unwrap_or(f64::NEG_INFINITY),
+        IlkSort::Tvl => to_f64(row.tvl, 18),
+    }
+}
+
+// the age of the oldest cached tvl in the table, if any
+pub fn ilk_table_title(rows: &[IlkRow]) -> String {
+    match rows.iter().filter_map(|row| row.tvl_valued).map(|valued| valued.elapsed().as_secs()).max() {
+        Some(age) => format!("ilks (:uninft tvl from {}s ago)", age),
+        None => "ilks".to_string(),
+    }
+}
+
+// one row per active ilk, sorted descending by `sort`
+pub fn paint_ilk_table<'a>(
+    rows: &[IlkRow],
+    sort: IlkSort,
+    par: U256,
+    xau: U256,
+    color_map: &HashMap<&str, Color>,
+) -> Table<'a> {
+    let mut rows = rows.iter().collect::<Vec<&IlkRow>>();
+    rows.sort_by(|a, b| ilk_column(b, sort).total_cmp(&ilk_column(a, sort)));
+    let now = chrono::Utc::now().timestamp();
+    let header = [
+        "ilk", "fee apr", "rack", "last drip", "tart", "tink", "dust", "line", "liqr", "chop",
+        "price", "tvl rico", "tvl usd",
+    ]
+    .iter()
+    .enumerate()
+    .map(|(i, title)| {
+        // the two tvl columns share one sort
+        let sorted = i > 0 && IlkSort::ALL[usize::min(i - 1, IlkSort::ALL.len() - 1)] == sort;
+        Cell::from(*title).style(match sorted {
+            true => Style::default().fg(Color::Yellow).add_modifier(Modifier::UNDERLINED),
+            false => Style::default(),
+        })
+    });
+    let optional = |x: Option<f64>, precision: usize| match x {
+        Some(x) => format!("{:.*}", precision, x),
+        None => "-".to_string(),
+    };
+    let body = rows.into_iter().map(|row| {
+        let ilk = &row.ilk;
+        // cached tvls are dimmed, the title says how old they are
+        let tvl_style = match row.tvl_valued {
+            Some(_) => Style::default().fg(Color::DarkGray),
+            None => Style::default(),
+        };
+        Row::new(vec![
+            Cell::from(row.name.clone()).style(
+                Style::default().fg(*color_map.get(row.name.as_str()).unwrap_or(&Color::Reset)),
+            ),
+            Cell::from(format!("{:.2}%", fee_apr(ilk.fee))),
+            Cell::from(format!("{:.6}", to_f64(ilk.rack, 27))),
+            Cell::from(format_duration(now - ilk.rho.as_u64() as i64)),
+            Cell::from(format!("{:.4}", to_f64(ilk.tart, 18))),
+            Cell::from(format!("{:.4}", ilk_column(row, IlkSort::Tink))),
+            Cell::from(format!("{:.2}", to_f64(ilk.dust, 45))),
+            Cell::from(format!("{:.2}", to_f64(ilk.line, 45))),
+            Cell::from(optional(row.liqr.map(|liqr| to_f64(liqr, 27)), 3)),
+            Cell::from(format!("{:.3}", to_f64(ilk.chop, 27))),
+            Cell::from(optional(row.price(), 6)),
+            Cell::from(format!("{:.2}", row.tvl_rico(par))).style(tvl_style),
+            Cell::from(format!("{:.2}", row.tvl_usd(xau))).style(tvl_style),
+        ])
+    });
+    Table::new(body)
+        .header(Row::new(header).style(Style::default().add_modifier(Modifier::BOLD)))
+        .widths(&ILK_TABLE_WIDTHS)
+        .column_spacing(1)
+}
+
+// per-token feeds of the :uninft hook and which token's liqr each deposited position is valued with
+pub fn paint_uni_ilk_detail(detail: &UniIlkDetail, current_time: NaiveDateTime) -> String {
+    let units = units::new();
+    let now = current_time.timestamp() as u64;
+    let mut lines = detail
+        .errors
+        .iter()
+        .map(|error| format!("  {}", error))
+        .collect::<Vec<String>>();
+    lines.push("  tokens:".to_string());
+    for row in detail.tokens.iter() {
+        let info = &row.info;
+        let price = feed_price(info.price, row.decimals);
+        let ttl = match info.ttl > U256::from(u64::MAX) {
+            true => "never expires".to_string(),
+            false => match info.ttl.as_u64().checked_sub(now) {
+                Some(left) => format!("expires in {}s", left),
+                None => format!("stale {}s", now - info.ttl.as_u64()),
+            },
+        };
+        lines.push(format!(
+            "    {} {:?}: src {:?} tag {} price {:.6} ({}) liqr {}",
+            row.symbol,
+            info.token,
+            info.src,
+            bytes32_to_string(info.tag),
+            price,
+            ttl,
+            (info.liqr * units.BLN / units.RAY).as_u128() as f64 / units.BLN_F64
+        ));
+    }
+    if !detail.positions.is_empty() {
+        lines.push("  positions:".to_string());
+    }
+    for (token_id, token0, token1) in detail.positions.iter() {
+        let symbol = |token: &H160| match detail.token(*token) {
+            Some(row) => row.symbol.clone(),
+            None => format!("{:?}", token),
+        };
+        lines.push(match detail.driver(*token0, *token1) {
+            Some(driver) => format!(
+                "    #{} {}/{}: liqr {} from {}",
+                token_id,
+                symbol(token0),
+                symbol(token1),
+                (driver.info.liqr * units.BLN / units.RAY).as_u128() as f64 / units.BLN_F64,
+                driver.symbol
+            ),
+            None => format!("    #{} {}/{}: no feed", token_id, symbol(token0), symbol(token1)),
+        });
+    }
+    lines.join("\n")
 }
 
 pub fn paint_footer(
     last_block: U64,
     last_refreshed: NaiveDateTime,
     ilk_shortcuts: &str,
-) -> Paragraph {
+) -> Paragraph<'_> {
     let footer_spans = vec![
         Spans::from(vec![
             Span::styled("Last Block: ", Style::default().add_modifier(Modifier::BOLD)),
@@ -185,7 +389,7 @@ pub fn paint_footer(
         ]),
         Spans::from(vec![
             Span::styled("global_controls: ", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw("'q' to quit, 'p' to pop last ilk, 'c' to clear active view, 's' to view settings\n"),
+            Span::raw("'q' to quit, 'p' to pop last ilk, 'c' to clear active view, 's' to view settings, 'o' to move uninft positions, 't' to plan borrows, 'i' to check a frob, 'k' for riskiest urns, 'v' to cycle watched addresses, 'g' to inspect an address, 'y' for portfolio totals, 'x' to stress test prices, 'j' for uninft positions, 'e' for uninft range charts, 'D' for protocol totals, 'O' for oracle feeds, tab to sort the ilk table\n"),
         ]),
         Spans::from(vec![
             Span::styled("ilk_shortcuts: ", Style::default().add_modifier(Modifier::BOLD)),
@@ -264,7 +468,7 @@ pub fn paint_marpar(
 // define the paint_menu function. It should take in a Vec<String> and return a Paragraph where each string is on a new line with numbering.
 // The first argument is the menu items, and the second argument is the selected index. The selected index should be highlighted.
 
-pub fn paint_menu(menu_items: Vec<&str>, selected_index: usize) -> Paragraph {
+pub fn paint_menu(menu_items: Vec<&str>, selected_index: usize) -> Paragraph<'_> {
     let mut menu_spans = vec![];
     for (i, item) in menu_items.iter().enumerate() {
         if i == selected_index {
@@ -309,6 +513,49 @@ pub fn paint_pricing_screen(mar: U256, par: U256, xau: U256) -> Paragraph<'stati
     )
 }
 
+pub fn paint_poke(
+    mar: U256,
+    par: U256,
+    way: U256,
+    how: U256,
+    cap: U256,
+    tau: U256,
+    current_time: NaiveDateTime,
+) -> Paragraph<'static> {
+    if cap.is_zero() || how.is_zero() {
+        return Paragraph::new("Awaiting vox data...");
+    }
+    let projection = project_poke(
+        par,
+        way,
+        how,
+        cap,
+        tau,
+        mar,
+        U256::from(current_time.timestamp()),
+    );
+    let dt = projection.dt.as_u64();
+    let par_f64 = |par: U256| par.as_u128() as f64 / units::new().RAY_F64;
+    let poke_text = format!(
+        "last poke: {} hours, {} minutes, {} seconds ago\nway: {:.6}% -> {:.6}% ({:+.6}%)\npar: {:.9} -> {:.9} ({:+.9})",
+        dt / 3600,
+        (dt % 3600) / 60,
+        dt % 60,
+        way_to_apr(way),
+        way_to_apr(projection.way),
+        way_to_apr(projection.way) - way_to_apr(way),
+        par_f64(par),
+        par_f64(projection.par),
+        par_f64(projection.par) - par_f64(par),
+    );
+    Paragraph::new(poke_text).block(
+        Block::default()
+            .title("poke")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::DarkGray)),
+    )
+}
+
 pub fn paint_newpalm2s<'a>(
     palms: Vec<&NewPalm2>,
     color_map: &'a std::collections::HashMap<&'a str, Color>,
@@ -440,17 +687,711 @@ pub fn paint_newpalm0s<'a>(
     Paragraph::new(text)
 }
 
-pub fn paint_settings(config: &crate::config::TermConfig) -> Paragraph {
+pub fn paint_settings(config: &crate::config::TermConfig) -> Paragraph<'_> {
     let text = format!(
-        "settings:\nrpc_url: {}\n refresh_freq: {} seconds \nwallet_address: {}\nilks: {}",
+        "settings:\nrpc_url: {}\n refresh_freq: {} seconds \nwallet_address: {}\nilks: {}\nwatchlist: {}",
         config.rpc.arb_rpc_url,
         config.rpc.refresh_seconds,
         config.urns.user_address,
-        config.urns.ilks.join(", ")
+        config.urns.ilks.join(", "),
+        config
+            .watchlist
+            .iter()
+            .flatten()
+            .map(|watch| format!("{} ({})", watch.label, watch.address))
+            .collect::<Vec<String>>()
+            .join(", ")
     );
     Paragraph::new(text)
 }
 
+pub fn paint_uninft_flow<'a>(state: &State, flow: &UniNftFlow) -> Paragraph<'a> {
+    let mut lines = vec![Spans::from(vec![
+        Span::styled("approved: ", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(match flow.approved {
+            true => "yes",
+            false => "no ('a' to approve)",
+        }),
+    ])];
+    for index in 0..flow.len() {
+        let (token_id, deposited) = flow.token_at(index).unwrap();
+        if index == 0 || index == flow.wallet.len() {
+            lines.push(Spans::from(Span::styled(
+                match deposited {
+                    false => "wallet:",
+                    true => "urn:",
+                },
+                Style::default().add_modifier(Modifier::BOLD),
+            )));
+        }
+        let text = format!(
+            "  [{}] {}",
+            match state.nft_selected.contains(&token_id) {
+                true => "x",
+                false => " ",
+            },
+            token_id
+        );
+        lines.push(Spans::from(match index == state.nft_cursor {
+            true => Span::styled(text, Style::default().add_modifier(Modifier::REVERSED)),
+            false => Span::raw(text),
+        }));
+    }
+    if flow.is_empty() {
+        lines.push(Spans::from(Span::raw("no positions in wallet or urn")));
+    }
+    if let Some(preview) = &flow.preview {
+        let fmt = |amounts: (U256, U256)| {
+            format!(
+                "{} / {}",
+                ethers::utils::format_units(amounts.0, preview.decimals0).unwrap(),
+                ethers::utils::format_units(amounts.1, preview.decimals1).unwrap()
+            )
+        };
+        lines.push(Spans::from(Span::styled(
+            format!(
+                "position {} ({:?} / {:?}):",
+                preview.token_id, preview.token0, preview.token1
+            ),
+            Style::default().add_modifier(Modifier::BOLD),
+        )));
+        lines.push(Spans::from(Span::raw(format!(
+            "  principal: {}",
+            fmt(preview.principal())
+        ))));
+        lines.push(Spans::from(Span::raw(format!(
+            "  'f' collect fees: {}",
+            fmt(preview.fees)
+        ))));
+        lines.push(Spans::from(Span::raw(format!(
+            "  '-' decrease {}%: receive {}",
+            state.liquidity_pct,
+            fmt(preview.decrease(state.liquidity_pct))
+        ))));
+        lines.push(Spans::from(Span::raw(format!(
+            "  '+' increase {}%: needs {}",
+            state.liquidity_pct,
+            fmt(preview.increase(state.liquidity_pct))
+        ))));
+        lines.push(Spans::from(Span::styled(
+            format!(
+                "  reverts under {} out / {} in at {}% slippage",
+                fmt(preview.decrease_min(state.liquidity_pct, state.slippage)),
+                fmt(preview.increase_min(state.liquidity_pct, state.slippage)),
+                state.slippage
+            ),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    lines.push(Spans::from(Span::raw(
+        "space to select, 'd' to deposit, 'w' to withdraw, '[' / ']' to change %, 'o' to close",
+    )));
+    if let Some(status) = &state.tx_status {
+        lines.push(Spans::from(vec![
+            Span::styled("tx: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(status.clone()),
+        ]));
+    }
+    Paragraph::new(lines)
+}
+
+pub fn paint_nft_breakdown<'a>(rows: &[NftRow]) -> Paragraph<'a> {
+    let units = units::new();
+    let total = rows
+        .iter()
+        .fold(U256::zero(), |acc, row| acc + row.breakdown.value);
+    let mut lines = Vec::<Spans>::new();
+    for row in rows.iter() {
+        let breakdown = &row.breakdown;
+        let position = &breakdown.position;
+        let amount = |x: U256, decimals: u32| ethers::utils::format_units(x, decimals).unwrap();
+        let (status, status_color) = match breakdown.status() {
+            RangeStatus::InRange => ("in range", Color::Green),
+            RangeStatus::Below => ("below range", Color::Yellow),
+            RangeStatus::Above => ("above range", Color::Yellow),
+        };
+        let (price_lower, price_upper) = position.price_range(row.decimals0, row.decimals1);
+        lines.push(Spans::from(vec![
+            Span::styled(
+                format!(
+                    "#{} {}/{} {}% ",
+                    breakdown.token_id,
+                    row.symbol0,
+                    row.symbol1,
+                    position.fee as f64 / 10_000.0
+                ),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(format!(
+                "ticks [{}, {}) at {} ",
+                position.tick_lower, position.tick_upper, breakdown.tick
+            )),
+            Span::styled(status, Style::default().fg(status_color)),
+        ]));
+        lines.push(Spans::from(format!(
+            "  range:   {:.6} - {:.6} {} per {}",
+            price_lower, price_upper, row.symbol1, row.symbol0
+        )));
+        lines.push(Spans::from(format!(
+            "  amounts: {} {} + {} {}",
+            amount(breakdown.principal.0, row.decimals0),
+            row.symbol0,
+            amount(breakdown.principal.1, row.decimals1),
+            row.symbol1
+        )));
+        lines.push(Spans::from(format!(
+            "  fees:    {} {} + {} {}",
+            amount(breakdown.fees.0, row.decimals0),
+            row.symbol0,
+            amount(breakdown.fees.1, row.decimals1),
+            row.symbol1
+        )));
+        lines.push(Spans::from(format!(
+            "  value:   {:.6} ({:.2}% of urn)",
+            breakdown.value.as_u128() as f64 / units.WAD_F64,
+            match total.is_zero() {
+                true => 0.0,
+                false => (breakdown.value * units.BLN / total).as_u128() as f64 / units.BLN_F64 * 100.0,
+            }
+        )));
+    }
+    Paragraph::new(lines)
+}
+
+pub fn paint_range_chart(series: &RangeSeries) -> Chart<'_> {
+    fn line<'a>(name: &'static str, data: &'a [(f64, f64)], color: Color) -> Dataset<'a> {
+        Dataset::default()
+            .name(name)
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(color))
+            .data(data)
+    }
+    let datasets = vec![
+        line("value", &series.value, Color::Cyan),
+        line("hodl", &series.hodl, Color::DarkGray),
+        line("liquidation", &series.threshold, Color::Red),
+        line("price", &series.current, Color::White),
+        line("range", &series.lower, Color::Yellow),
+        line("", &series.upper, Color::Yellow),
+    ];
+    let label = |x: f64| Span::raw(format!("{:.4}", x));
+    let x_mid = (series.x_bounds[0] * series.x_bounds[1]).sqrt();
+    let y_mid = (series.y_bounds[0] + series.y_bounds[1]) / 2.0;
+    Chart::new(datasets)
+        .block(
+            Block::default()
+                .title(format!(
+                    "{} range ('tab' for next position)",
+                    series.label
+                ))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::LightYellow)),
+        )
+        .x_axis(
+            Axis::default()
+                .title("price")
+                .bounds(series.x_bounds)
+                .labels(vec![
+                    label(series.x_bounds[0]),
+                    label(x_mid),
+                    label(series.x_bounds[1]),
+                ]),
+        )
+        .y_axis(
+            Axis::default()
+                .title("value")
+                .bounds(series.y_bounds)
+                .labels(vec![
+                    label(series.y_bounds[0]),
+                    label(y_mid),
+                    label(series.y_bounds[1]),
+                ]),
+        )
+}
+
+pub fn paint_protocol<'a>(protocol: &Protocol) -> Paragraph<'a> {
+    let units = units::new();
+    let wad = |x: U256| ethers::utils::format_units(x, 18).unwrap();
+    let rad = |x: U256| ethers::utils::format_units(x, 45).unwrap();
+    let par = (protocol.par * units.BLN / units.RAY).as_u128() as f64 / units.BLN_F64;
+    Paragraph::new(vec![
+        Spans::from(vec![
+            Span::styled("debt: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(format!(
+                "{} / {} rico (par {:.6})",
+                wad(protocol.debt),
+                wad(protocol.ceil),
+                par
+            )),
+        ]),
+        Spans::from(vec![
+            Span::styled("joy: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled(wad(protocol.joy), Style::default().fg(Color::Green)),
+            Span::styled("  sin: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled(
+                rad(protocol.sin),
+                Style::default().fg(match protocol.sin.is_zero() {
+                    true => Color::Gray,
+                    false => Color::Red,
+                }),
+            ),
+            Span::styled("  rest: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(rad(protocol.rest)),
+        ]),
+        Spans::from(Span::styled(
+            "utilization (debt / ceil, tart * rack / line):",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ])
+}
+
+// green until 75% of the limit is used, yellow until 90%, red beyond
+fn utilization_color(ratio: f64) -> Color {
+    match ratio {
+        r if r >= 0.9 => Color::Red,
+        r if r >= 0.75 => Color::Yellow,
+        _ => Color::Green,
+    }
+}
+
+// one gauge for the global ceiling then one per ilk
+pub fn paint_utilization_gauges<'a>(protocol: &Protocol) -> Vec<Gauge<'a>> {
+    let gauge = |label: String, ratio: f64| {
+        Gauge::default()
+            .gauge_style(Style::default().fg(utilization_color(ratio)).bg(Color::Black))
+            .ratio(ratio)
+            .label(format!("{} {:.2}%", label, ratio * 100.0))
+    };
+    std::iter::once(gauge(
+        "ceil".to_string(),
+        utilization(protocol.debt, protocol.ceil),
+    ))
+    .chain(
+        protocol
+            .ilks
+            .iter()
+            .map(|ilk| gauge(ilk.ilk.clone(), utilization(ilk.debt, ilk.line))),
+    )
+    .collect()
+}
+
+// "1h 02m 03s", the countdown shown next to each feed
+fn format_duration(seconds: i64) -> String {
+    let seconds = seconds.abs();
+    format!(
+        "{}h {:02}m {:02}s",
+        seconds / 3600,
+        (seconds % 3600) / 60,
+        seconds % 60
+    )
+}
+
+pub fn paint_oracles<'a>(feeds: &[OracleFeed]) -> Paragraph<'a> {
+    let now = chrono::Utc::now().timestamp();
+    let mut lines = vec![Spans::from(Span::styled(
+        format!(
+            "{:<16} {:>18} {:>21} {:>16}  status",
+            "feed", "value", "expires (UTC)", "countdown"
+        ),
+        Style::default().add_modifier(Modifier::BOLD),
+    ))];
+    for feed in feeds.iter() {
+        let status = feed.status(now);
+        let status_color = match status {
+            FeedStatus::Fresh => Color::Green,
+            FeedStatus::Expiring => Color::Yellow,
+            FeedStatus::Stale | FeedStatus::Failed => Color::Red,
+        };
+        let (value, expiry, countdown) = match (&feed.read, feed.expires_in(now)) {
+            (FeedRead::Value { val, ttl }, Some(left)) => (
+                format!("{:.6}", to_f64(*val, 27)),
+                NaiveDateTime::from_timestamp_opt(ttl.as_u64() as i64, 0)
+                    .map_or("-".to_string(), |time| time.to_string()),
+                match left < 0 {
+                    true => format!("-{}", format_duration(left)),
+                    false => format_duration(left),
+                },
+            ),
+            (FeedRead::Value { val, .. }, None) => (
+                format!("{:.6}", to_f64(*val, 27)),
+                "never".to_string(),
+                "-".to_string(),
+            ),
+            _ => ("-".to_string(), "-".to_string(), "-".to_string()),
+        };
+        let warning = match (status, &feed.read) {
+            (_, FeedRead::ErrTtl) => "stale: pull reverted with ErrTTL".to_string(),
+            (_, FeedRead::Failed(e)) => format!("pull failed: {}", e),
+            (_, FeedRead::Unresolved(e)) => e.clone(),
+            (FeedStatus::Stale, _) => "stale".to_string(),
+            (FeedStatus::Expiring, _) => "about to expire".to_string(),
+            _ => "ok".to_string(),
+        };
+        lines.push(Spans::from(vec![
+            Span::raw(format!(
+                "{:<16} {:>18} {:>21} {:>16}  ",
+                feed.label, value, expiry, countdown
+            )),
+            Span::styled(warning, Style::default().fg(status_color)),
+        ]));
+        if let FeedRead::Unresolved(_) = feed.read {
+            continue;
+        }
+        lines.push(Spans::from(Span::styled(
+            format!("  src {:?} tag {}", feed.src, bytes32_to_string(feed.tag)),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    Paragraph::new(lines)
+}
+
+pub fn paint_planner<'a>(urns: &[UrnData], target_safety: f64) -> Paragraph<'a> {
+    let units = units::new();
+    let target = U256::from((target_safety * units.BLN_F64) as u64) * units.BLN;
+    let wad = |x: U256| ethers::utils::format_units(x, 18).unwrap();
+    let mut lines = vec![Spans::from(vec![
+        Span::styled("target safety: ", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(format!("{:.2} (left / right to adjust)", target_safety)),
+    ])];
+    for urn in urns.iter() {
+        let planner = urn.planner();
+        let (add_value, add_ink) = planner.collateral_for_safety(target);
+        lines.push(Spans::from(Span::styled(
+            format!("{} (safety {:.5})", urn.ink_name, urn.safety),
+            Style::default().add_modifier(Modifier::BOLD),
+        )));
+        lines.push(Spans::from(Span::raw(format!(
+            "  max borrow: {} rico ({} art)",
+            wad(planner.max_borrow()),
+            wad(planner.max_borrow_art())
+        ))));
+        lines.push(Spans::from(Span::raw(format!(
+            "  repay to target: {} rico",
+            wad(planner.repay_for_safety(target))
+        ))));
+        lines.push(Spans::from(Span::raw(match urn.ninks {
+            Some(_) => format!("  collateral to target: +{} value", wad(add_value)),
+            None => format!(
+                "  collateral to target: +{} value (+{} {})",
+                wad(add_value),
+                ethers::utils::format_units(add_ink, urn.ink_decimals()).unwrap(),
+                urn.gem.as_ref().map_or(urn.ink_name.as_str(), |gem| gem.symbol.as_str())
+            ),
+        })));
+    }
+    Paragraph::new(lines)
+}
+
+pub fn paint_what_if<'a>(
+    input: &str,
+    error: Option<&String>,
+    what_if: Option<&WhatIf>,
+) -> Paragraph<'a> {
+    let mut lines = vec![
+        Spans::from(vec![
+            Span::styled("frob> ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(input.to_string()),
+            Span::styled("_", Style::default().add_modifier(Modifier::SLOW_BLINK)),
+        ]),
+        Spans::from(Span::styled(
+            "<ilk> <dink> <dart>, enter to check, esc to close",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+    if let Some(error) = error {
+        lines.push(Spans::from(Span::styled(
+            error.to_string(),
+            Style::default().fg(Color::Red),
+        )));
+    }
+    if let Some(what_if) = what_if {
+        let decimals = what_if.decimals;
+        let signed = |x: I256, decimals: u32| {
+            format!(
+                "{}{}",
+                match x.is_negative() {
+                    true => "-",
+                    false => "",
+                },
+                ethers::utils::format_units(x.unsigned_abs(), decimals).unwrap()
+            )
+        };
+        lines.push(Spans::from(Span::raw(format!(
+            "{}: ink {} {}, art {} {}",
+            what_if.query.ilk,
+            ethers::utils::format_units(what_if.frob.ink, decimals).unwrap(),
+            signed(what_if.frob.dink, decimals),
+            ethers::utils::format_units(what_if.frob.art, 18).unwrap(),
+            signed(what_if.frob.dart, 18),
+        ))));
+        if what_if.violations.is_empty() {
+            lines.push(Spans::from(Span::styled(
+                "ok, no dust/line/ceil violations",
+                Style::default().fg(Color::Green),
+            )));
+        }
+        for violation in what_if.violations.iter() {
+            lines.push(Spans::from(Span::styled(
+                violation.to_string(),
+                Style::default().fg(Color::Red),
+            )));
+        }
+    }
+    Paragraph::new(lines)
+}
+
+pub fn paint_stress<'a>(
+    input: &str,
+    error: Option<&String>,
+    stress: &[StressedUrn],
+    color_map: &std::collections::HashMap<&str, Color>,
+) -> Paragraph<'a> {
+    let units = units::new();
+    let wad = |x: U256| x.as_u128() as f64 / units.WAD_F64;
+    let mut lines = vec![
+        Spans::from(vec![
+            Span::styled("shock> ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(input.to_string()),
+            Span::styled("_", Style::default().add_modifier(Modifier::SLOW_BLINK)),
+        ]),
+        Spans::from(Span::styled(
+            "<pct> for every feed and/or <feed>=<pct> per feed tag, enter to run, esc to close",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+    if let Some(error) = error {
+        lines.push(Spans::from(Span::styled(
+            error.to_string(),
+            Style::default().fg(Color::Red),
+        )));
+    }
+    if stress.is_empty() {
+        return Paragraph::new(lines);
+    }
+    lines.push(Spans::from(Span::styled(
+        format!(
+            "{:<10} {:>16} {:>16} {:>16} {:>10} {:>10}",
+            "ilk", "loan", "value", "shocked", "safety", "shocked"
+        ),
+        Style::default().add_modifier(Modifier::BOLD),
+    )));
+    for urn in stress.iter() {
+        let (color, note) = match (urn.liquidatable(), urn.loan.is_zero()) {
+            (true, _) => (Color::Red, "  liquidatable"),
+            (false, false) if urn.safety_after() < 1.2 => (Color::Yellow, ""),
+            _ => (Color::Reset, ""),
+        };
+        lines.push(Spans::from(vec![
+            Span::styled(
+                format!("{:<10} ", urn.ilk),
+                Style::default().fg(color_map
+                    .get(urn.ilk.as_str())
+                    .unwrap_or(&Color::Reset)
+                    .to_owned()),
+            ),
+            Span::raw(format!(
+                "{:>16.6} {:>16.6} {:>16.6} {:>10.5} ",
+                wad(urn.loan),
+                wad(urn.value),
+                wad(urn.shocked_value),
+                urn.safety_before()
+            )),
+            Span::styled(
+                format!("{:>10.5}{}", urn.safety_after(), note),
+                Style::default().fg(color),
+            ),
+        ]));
+    }
+    Paragraph::new(lines)
+}
+
+pub fn paint_inspect<'a>(input: &str, error: Option<&String>) -> Paragraph<'a> {
+    let mut lines = vec![
+        Spans::from(vec![
+            Span::styled("address> ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(input.to_string()),
+            Span::styled("_", Style::default().add_modifier(Modifier::SLOW_BLINK)),
+        ]),
+        Spans::from(Span::styled(
+            "0x..., enter to inspect, empty to go back, esc to close",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+    if let Some(error) = error {
+        lines.push(Spans::from(Span::styled(
+            error.to_string(),
+            Style::default().fg(Color::Red),
+        )));
+    }
+    Paragraph::new(lines)
+}
+
+pub fn paint_portfolio<'a>(
+    urns: &[UrnData],
+    mar: U256,
+    xau: U256,
+    color_map: &std::collections::HashMap<&str, Color>,
+) -> Paragraph<'a> {
+    let units = units::new();
+    let portfolio = Portfolio::new(urns);
+    let wad = |x: U256| x.as_u128() as f64 / units.WAD_F64;
+    let safety_color = |safety: f64| match safety {
+        s if s < 1.0 => Color::Red,
+        s if s < 1.2 => Color::Yellow,
+        _ => Color::Reset,
+    };
+    let mut lines = vec![Spans::from(Span::styled(
+        format!(
+            "{:<10} {:>16} {:>16} {:>10} {:>8}",
+            "ilk", "value", "loan", "safety", "fee %"
+        ),
+        Style::default().add_modifier(Modifier::BOLD),
+    ))];
+    for (i, urn) in urns.iter().enumerate() {
+        let weakest = portfolio.weakest == Some(i);
+        let style = match weakest {
+            true => Style::default()
+                .fg(safety_color(urn.safety))
+                .add_modifier(Modifier::BOLD),
+            false => Style::default(),
+        };
+        lines.push(Spans::from(vec![
+            Span::styled(
+                format!("{:<10} ", urn.ink_name),
+                Style::default().fg(color_map
+                    .get(urn.ink_name.as_str())
+                    .unwrap_or(&Color::Reset)
+                    .to_owned()),
+            ),
+            Span::styled(
+                format!(
+                    "{:>16.6} {:>16.6} {:>10.5} {:>8.3}{}",
+                    wad(urn.value),
+                    wad(urn.loan),
+                    urn.safety,
+                    fee_apr(urn.fee),
+                    match weakest {
+                        true => "  <- weakest",
+                        false => "",
+                    }
+                ),
+                style,
+            ),
+        ]));
+    }
+    lines.push(Spans::from(Span::styled(
+        format!(
+            "{:<10} {:>16.6} {:>16.6} {:>10.5} {:>8.3}",
+            "total",
+            wad(portfolio.value),
+            wad(portfolio.loan),
+            portfolio.safety(),
+            portfolio.fee_apr
+        ),
+        Style::default()
+            .fg(match portfolio.loan.is_zero() {
+                true => Color::Reset,
+                false => safety_color(portfolio.safety()),
+            })
+            .add_modifier(Modifier::BOLD),
+    )));
+    lines.push(Spans::from(""));
+    lines.push(Spans::from(format!(
+        "debt: {:.6} RICO, accruing ~{:.6} RICO (~{:.2} USD) per day",
+        wad(portfolio.debt),
+        wad(portfolio.daily_interest),
+        portfolio.daily_interest_usd(mar, xau)
+    )));
+    Paragraph::new(lines)
+}
+
+pub fn paint_leaderboard<'a>(
+    rows: &[LeaderboardRow],
+    block: U64,
+    ilks: &[String],
+    sort: LeaderboardSort,
+    color_map: &std::collections::HashMap<&str, Color>,
+) -> Paragraph<'a> {
+    let units = units::new();
+    let mut rows = rows
+        .iter()
+        .filter(|row| ilks.is_empty() || ilks.contains(&row.ilk))
+        .collect::<Vec<&LeaderboardRow>>();
+    match sort {
+        // urns without a loan have a safety of 0, keep them at the bottom with unreadable ones
+        LeaderboardSort::Safety => rows.sort_by(|a, b| {
+            let safety = |row: &LeaderboardRow| match row.loan.is_zero() || row.error.is_some() {
+                true => f64::INFINITY,
+                false => row.safety(),
+            };
+            safety(a).total_cmp(&safety(b))
+        }),
+        LeaderboardSort::Debt => rows.sort_by_key(|row| std::cmp::Reverse(row.debt())),
+        LeaderboardSort::Value => rows.sort_by_key(|row| std::cmp::Reverse(row.value)),
+    }
+    // gem urns are revalued every block, :uninft ones only when they change
+    let uni_age = rows
+        .iter()
+        .filter_map(|row| row.valued)
+        .map(|valued| valued.elapsed().as_secs())
+        .max();
+    let header = Spans::from(vec![
+        Span::styled(
+            format!(
+                "{:<44} {:<8} {:>10} {:>16} {:>16}   (sorted by {:?}, tab to change)",
+                "usr", "ilk", "safety", "debt", "value", sort
+            ),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            match uni_age {
+                Some(age) => format!("  block {}, :uninft values up to {}s old", block, age),
+                None => format!("  block {}", block),
+            },
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]);
+    let lines = rows.iter().map(|row| {
+        if let Some(error) = &row.error {
+            return Spans::from(vec![
+                Span::raw(format!("{:<44} {:<8} ", format!("{:?}", row.usr), row.ilk)),
+                Span::styled(
+                    format!("couldn't read ink: {}", error),
+                    Style::default().fg(Color::Red),
+                ),
+            ]);
+        }
+        let safety = row.safety();
+        let safety_color = match row.loan.is_zero() {
+            true => Color::DarkGray,
+            false if safety < 1.0 => Color::Red,
+            false if safety < 1.2 => Color::Yellow,
+            false => Color::Reset,
+        };
+        Spans::from(vec![
+            Span::raw(format!("{:<44} ", format!("{:?}", row.usr))),
+            Span::styled(
+                format!("{:<8} ", row.ilk),
+                Style::default().fg(color_map
+                    .get(row.ilk.as_str())
+                    .unwrap_or(&Color::Reset)
+                    .to_owned()),
+            ),
+            Span::styled(
+                format!("{:>10.5} ", safety),
+                Style::default().fg(safety_color),
+            ),
+            Span::raw(format!(
+                "{:>16.6} {:>16.6}",
+                row.debt().as_u128() as f64 / units.WAD_F64,
+                row.value.as_u128() as f64 / units.WAD_F64
+            )),
+        ])
+    });
+    Paragraph::new(std::iter::once(header).chain(lines).collect::<Vec<Spans>>())
+}
+
 pub fn paint_active_view<'a>(
     state: &State,
     data: &'a ChainData,
@@ -459,33 +1400,27 @@ pub fn paint_active_view<'a>(
 ) -> (Paragraph<'a>, &'a str) {
     let (active_text, active_title) = match &state.selected_active_view {
         SelectedActiveView::Settings => (paint_settings(config), "settings"),
-        SelectedActiveView::Ilk => {
-            if !state.active_ilk.is_empty() {
-                let all_ilk_data = &data.ilks;
-                // now, instead, iterate over all active ilks and append their data to the active view
-                let formatted_str = state
-                    .active_ilk
-                    .iter()
-                    .enumerate()
-                    .map(|(index, ilk)| {
-                        format!(
-                            "ilk: {}\n{}",
-                            ilk,
-                            match all_ilk_data.get(index) {
-                                Some(ilk) => {
-                                    paint_ilk(ilk, data.last_refreshed)
-                                }
-                                _ => "  Awaiting ilk data...".to_string(),
-                            }
-                        )
-                    })
-                    .collect::<Vec<String>>()
-                    .join("\n");
-                (Paragraph::new(formatted_str), "ilk_view")
-            } else {
-                (Paragraph::new("No active view".to_string()), "active_view")
-            }
-        }
+        // the table is drawn by the caller above this, which only carries the :uninft feeds
+        SelectedActiveView::Ilk => match (state.active_ilk.is_empty(), data.ilks.is_empty()) {
+            (true, _) => (Paragraph::new("No active view".to_string()), "active_view"),
+            (false, true) => (Paragraph::new("Awaiting ilk data..."), "ilk_view"),
+            (false, false) => match &data.uni_ilk_detail {
+                Some(detail) => (
+                    Paragraph::new(format!(
+                        "ilk: :uninft\n{}",
+                        paint_uni_ilk_detail(detail, data.last_refreshed)
+                    )),
+                    "ilk_view",
+                ),
+                None => (
+                    Paragraph::new(Span::styled(
+                        "tab to change the sort column",
+                        Style::default().fg(Color::DarkGray),
+                    )),
+                    "ilk_view",
+                ),
+            },
+        },
         SelectedActiveView::NewPalm2 => {
             if !data.logs.is_empty() {
                 let filtered_logs = data
@@ -530,6 +1465,67 @@ pub fn paint_active_view<'a>(
                 (Paragraph::new("Awaiting NewPalm0 event..."), "sys-events")
             }
         }
+        SelectedActiveView::WhatIf => (
+            paint_what_if(
+                &state.input_buffer,
+                state.input_error.as_ref(),
+                data.what_if.as_ref(),
+            ),
+            "what if",
+        ),
+        SelectedActiveView::Stress => (
+            paint_stress(
+                &state.input_buffer,
+                state.input_error.as_ref(),
+                &data.stress,
+                color_map,
+            ),
+            "stress test",
+        ),
+        SelectedActiveView::Inspect => (
+            paint_inspect(&state.input_buffer, state.input_error.as_ref()),
+            "inspect address",
+        ),
+        SelectedActiveView::Leaderboard => match data.leaderboard.is_empty() {
+            true => (Paragraph::new("Indexing urns..."), "riskiest urns"),
+            false => (
+                paint_leaderboard(
+                    &data.leaderboard,
+                    data.block,
+                    &state.active_ilk,
+                    state.leaderboard_sort,
+                    color_map,
+                ),
+                "riskiest urns",
+            ),
+        },
+        SelectedActiveView::NftBreakdown => match data.nft_rows.is_empty() {
+            true => (Paragraph::new("No deposited uninft positions"), "uninft positions"),
+            false => (paint_nft_breakdown(&data.nft_rows), "uninft positions"),
+        },
+        // drawn as a chart by the caller once there is a position to plot
+        SelectedActiveView::RangeChart => (
+            Paragraph::new("No deposited uninft positions"),
+            "uninft range",
+        ),
+        // drawn with gauges by the caller once the totals are in
+        SelectedActiveView::Protocol => (Paragraph::new("Awaiting vat totals..."), "protocol"),
+        SelectedActiveView::Oracles => match data.oracle_feeds.is_empty() {
+            true => (Paragraph::new("Awaiting feeds..."), "oracles"),
+            false => (paint_oracles(&data.oracle_feeds), "oracles"),
+        },
+        SelectedActiveView::Portfolio => (
+            paint_portfolio(&data.urn_data, data.mar, data.xau, color_map),
+            "portfolio",
+        ),
+        SelectedActiveView::Planner => (
+            paint_planner(&data.urn_data, state.target_safety),
+            "planner",
+        ),
+        SelectedActiveView::UniNft => match &data.uninft_flow {
+            Some(flow) => (paint_uninft_flow(state, flow), "uninft"),
+            None => (Paragraph::new("Awaiting uninft positions..."), "uninft"),
+        },
         _ => (Paragraph::new("No active view"), "active_view"),
     };
 
@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use ethers::prelude::*;
+use ricolib::{
+    ddso::{feedbase::Feedbase, vat::Vat, vox::Vox},
+    math::units,
+    poke::{project_poke, way_to_apr},
+};
+
+use crate::{
+    actions::{self, SignerClient},
+    config::{PokerConfig, TermConfig},
+    keeper::{flag_value, log},
+};
+
+// `announced` is the tau a dry run last said it would poke at, nothing changes until
+// someone else pokes so it isn't repeated every poll
+#[allow(clippy::too_many_arguments)]
+async fn poke_if_due(
+    provider: &Arc<Provider<Http>>,
+    signer: &Option<Arc<SignerClient>>,
+    poker_config: &PokerConfig,
+    vat: &Vat<Provider<Http>>,
+    vox: &Vox<Provider<Http>>,
+    feedbase: &Feedbase<Provider<Http>>,
+    dry_run: bool,
+    announced: &mut Option<U256>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let units = units::new();
+    let block = provider
+        .get_block(BlockNumber::Latest)
+        .await?
+        .ok_or("latest block not found")?;
+    let (src, tag) = vox.tip().await;
+    let mar = U256::from_big_endian(feedbase.pull(src, tag).await.0.as_bytes());
+    let way = vox.way().await;
+    let tau = vox.tau().await;
+    let projection = project_poke(
+        vat.par().await,
+        way,
+        vox.how().await,
+        vox.cap().await,
+        tau,
+        mar,
+        block.timestamp,
+    );
+    let way_change = (way_to_apr(projection.way) - way_to_apr(way)).abs();
+    if projection.dt < U256::from(poker_config.interval_seconds)
+        && way_change < poker_config.min_way_change
+    {
+        return Ok(());
+    }
+    if dry_run && *announced == Some(tau) {
+        return Ok(());
+    }
+    log(format!(
+        "{}s since last poke, way {:.6}% -> {:.6}%",
+        projection.dt,
+        way_to_apr(way),
+        way_to_apr(projection.way)
+    ));
+    let gas_price = provider.get_gas_price().await?;
+    if gas_price > U256::from(poker_config.max_gas_price_gwei) * units.BLN {
+        log(format!("gas price {} gwei over limit, skipping", gas_price / units.BLN));
+        return Ok(());
+    }
+    match (signer, dry_run) {
+        (Some(signer), false) => match Vox::new(signer, vox.address).poke().await {
+            Ok(Some(receipt)) => log(format!("poke mined in {:?}", receipt.transaction_hash)),
+            Ok(None) => log("poke dropped from mempool".to_string()),
+            Err(e) => log(format!("poke failed: {}", e)),
+        },
+        _ => {
+            log("would poke".to_string());
+            *announced = Some(tau);
+        }
+    }
+    Ok(())
+}
+
+// `ricoterm poker [--rpc <url>] [--dry-run]`, pokes the vox on a schedule or when way drifts
+pub async fn run(config: TermConfig, args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let rpc = flag_value(args, "--rpc").unwrap_or(config.rpc.arb_rpc_url.clone());
+    let dry_run = args.iter().any(|arg| arg == "--dry-run");
+    let provider = Arc::new(Provider::<Http>::try_from(rpc.as_str())?);
+    let signer = actions::load_signer(&config, &provider).await?;
+    if signer.is_none() && !dry_run {
+        return Err("poker mode needs a [signer] unless run with --dry-run".into());
+    }
+    let poker_config = config
+        .poker
+        .as_ref()
+        .ok_or("poker mode needs a [poker] section in term.toml")?;
+    let diamond: Address = config.rico.diamond.parse()?;
+    let vat = Vat::new(&provider, diamond);
+    let vox = Vox::new(&provider, diamond);
+    let feedbase = Feedbase::new(&provider, config.rico.feedbase.parse()?);
+    log(format!(
+        "poking {} via {}{}",
+        diamond,
+        rpc,
+        match dry_run {
+            true => " (dry run)",
+            false => "",
+        }
+    ));
+    let mut announced = None;
+    loop {
+        if let Err(e) = poke_if_due(
+            &provider,
+            &signer,
+            poker_config,
+            &vat,
+            &vox,
+            &feedbase,
+            dry_run,
+            &mut announced,
+        )
+        .await
+        {
+            log(format!("poke check failed: {}", e));
+        }
+        tokio::time::sleep(tokio::time::Duration::from_millis(poker_config.poll_millis)).await;
+    }
+}
@@ -1,6 +1,14 @@
-use std::default;
+use std::{default, time::Instant};
 
-use ethers::types::U256;
+use ethers::types::{Address, H256, I256, U256};
+use ricolib::{
+    ilks::{GemCache, GemMeta},
+    math::{rpow, units},
+    planner::Planner,
+    ddso::vat::Ilk,
+    valuation::{value_position, UniNftBreakdown, UniTokenInfo},
+    validation::{Frob, FrobViolation},
+};
 
 #[derive(Debug, Clone)]
 pub struct UrnData {
@@ -12,6 +20,18 @@ pub struct UrnData {
     pub loan: U256,
     pub value: U256,
     pub safety: f64,
+    pub rack: U256,
+    pub par: U256,
+    pub dust: U256,
+    pub line: U256,
+    pub tart: U256,
+    pub fee: U256,
+    // None for :uninft
+    pub gem: Option<GemMeta>,
+    // gem balance of the urn's owner, what's left to top the urn up with
+    pub wallet: U256,
+    // :uninft positions the owner holds but hasn't deposited
+    pub wallet_nfts: Option<Vec<U256>>,
 }
 
 impl UrnData {
@@ -25,11 +45,683 @@ impl UrnData {
             value: U256::zero(),
             safety: 0.0,
             ninks: None,
+            rack: U256::zero(),
+            par: U256::zero(),
+            dust: U256::zero(),
+            line: U256::zero(),
+            tart: U256::zero(),
+            fee: U256::zero(),
+            gem: None,
+            wallet: U256::zero(),
+            wallet_nfts: None,
         }
     }
+
+    pub fn planner(&self) -> Planner {
+        Planner {
+            ink: self.ink,
+            art: self.art,
+            value: self.value,
+            rack: self.rack,
+            par: self.par,
+            dust: self.dust,
+            line: self.line,
+            tart: self.tart,
+        }
+    }
+
+    // empty panels shown until the first fetch for a set of ilks lands
+    pub fn placeholders(ilks: &[String]) -> Vec<UrnData> {
+        ilks.iter()
+            .map(|ilk| UrnData {
+                ink_name: ilk.to_string(),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    // decimals of ink and wallet amounts, 18 until the gem is resolved
+    pub fn ink_decimals(&self) -> u32 {
+        self.gem.as_ref().map_or(18, |gem| gem.decimals)
+    }
+
+    pub fn has_position(&self) -> bool {
+        !self.ink.is_zero() || !self.art.is_zero()
+    }
 }
 impl default::Default for UrnData {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[derive(Debug, Clone, Default)]
+pub struct UniNftFlow {
+    pub wallet: Vec<U256>,
+    pub deposited: Vec<U256>,
+    pub approved: bool,
+    pub preview: Option<PositionPreview>,
+}
+
+// amounts for the wallet position under the cursor, used to preview collect/liquidity changes
+#[derive(Debug, Clone)]
+pub struct PositionPreview {
+    pub token_id: U256,
+    pub token0: Address,
+    pub token1: Address,
+    pub decimals0: u32,
+    pub decimals1: u32,
+    pub liquidity: u128,
+    pub total: (U256, U256),
+    pub fees: (U256, U256),
+}
+
+impl PositionPreview {
+    pub fn principal(&self) -> (U256, U256) {
+        (
+            self.total.0.saturating_sub(self.fees.0),
+            self.total.1.saturating_sub(self.fees.1),
+        )
+    }
+
+    // what a decrease of `pct` percent pays out, fees are collected in the same multicall
+    pub fn decrease(&self, pct: u8) -> (U256, U256) {
+        let principal = self.principal();
+        (
+            principal.0 * pct / 100 + self.fees.0,
+            principal.1 * pct / 100 + self.fees.1,
+        )
+    }
+
+    // amounts needed to grow the position by `pct` percent at the current price
+    pub fn increase(&self, pct: u8) -> (U256, U256) {
+        let principal = self.principal();
+        (principal.0 * pct / 100, principal.1 * pct / 100)
+    }
+
+    // amount0Min/amount1Min for a decrease, only the principal is checked by the manager,
+    // the fees are collected afterwards
+    pub fn decrease_min(&self, pct: u8, slippage: f64) -> (U256, U256) {
+        let principal = self.principal();
+        (
+            with_slippage(principal.0 * pct / 100, slippage),
+            with_slippage(principal.1 * pct / 100, slippage),
+        )
+    }
+
+    pub fn increase_min(&self, pct: u8, slippage: f64) -> (U256, U256) {
+        let (amount0, amount1) = self.increase(pct);
+        (with_slippage(amount0, slippage), with_slippage(amount1, slippage))
+    }
+}
+
+// `amount` less `slippage` percent, to the basis point
+pub fn with_slippage(amount: U256, slippage: f64) -> U256 {
+    let bps = ((100.0 - slippage) * 100.0).round().clamp(0.0, 10_000.0) as u64;
+    amount * bps / 10_000
+}
+
+impl UniNftFlow {
+    // wallet positions are listed first, followed by the ones already in the urn
+    pub fn token_at(&self, index: usize) -> Option<(U256, bool)> {
+        match index < self.wallet.len() {
+            true => self.wallet.get(index).map(|id| (*id, false)),
+            false => self
+                .deposited
+                .get(index - self.wallet.len())
+                .map(|id| (*id, true)),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.wallet.len() + self.deposited.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// "<ilk> <dink> <dart>" as typed into the what-if prompt, amounts in whole units
+#[derive(Debug, Clone)]
+pub struct FrobQuery {
+    pub ilk: String,
+    pub dink: I256,
+    pub dart: I256,
+}
+
+impl FrobQuery {
+    // `known_ilks` are the ilks the vat is known to have, anything else would revert the reads
+    pub fn parse(input: &str, known_ilks: &[String], gems: &GemCache) -> Result<Self, String> {
+        let parts = input.split_whitespace().collect::<Vec<&str>>();
+        if parts.len() != 3 {
+            return Err("expected <ilk> <dink> <dart>".to_string());
+        }
+        let ilk = parts[0].to_string();
+        if !known_ilks.contains(&ilk) {
+            return Err(format!("{} is not a known ilk", ilk));
+        }
+        let dink = match ilk.as_str() {
+            ":uninft" => I256::zero(),
+            _ => parse_signed(parts[1], gems.decimals(&ilk))
+                .ok_or(format!("{} is not a {} amount", parts[1], ilk))?,
+        };
+        let dart = parse_signed(parts[2], 18).ok_or(format!("{} is not a rico amount", parts[2]))?;
+        Ok(Self { ilk, dink, dart })
+    }
+}
+
+fn parse_signed(amount: &str, decimals: u32) -> Option<I256> {
+    let (negative, amount) = match amount.strip_prefix('-') {
+        Some(amount) => (true, amount),
+        None => (false, amount),
+    };
+    let amount: U256 = ethers::utils::parse_units(amount, decimals).ok()?.into();
+    let amount = I256::try_from(amount).ok()?;
+    match negative {
+        true => Some(-amount),
+        false => Some(amount),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WhatIf {
+    pub query: FrobQuery,
+    pub frob: Frob,
+    pub violations: Vec<FrobViolation>,
+    // of the query's ilk, for formatting dink and ink
+    pub decimals: u32,
+}
+
+// An indexed urn with just enough to rank it, ink and art come from the urn index and
+// the feed, liqr and rack are read once per ilk
+#[derive(Debug, Clone)]
+pub struct LeaderboardRow {
+    pub usr: Address,
+    pub ilk: String,
+    // gem ink, or the number of positions for :uninft
+    pub ink: U256,
+    pub art: U256,
+    pub rack: U256,
+    pub value: U256,
+    pub loan: U256,
+    // :uninft values are reused until the urn changes, when this one was computed
+    pub valued: Option<Instant>,
+    // the urn's ink couldn't be read, value is left at zero
+    pub error: Option<String>,
+}
+
+impl LeaderboardRow {
+    pub fn debt(&self) -> U256 {
+        self.art * self.rack / units::new().RAY
+    }
+
+    pub fn safety(&self) -> f64 {
+        let units = units::new();
+        match self.loan.is_zero() {
+            true => 0.0,
+            false => (units.BLN * self.value / self.loan).as_u128() as f64 / units.BLN_F64,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Watched {
+    pub label: String,
+    pub address: Address,
+    pub ilks: Vec<String>,
+}
+
+// value and loan summed over every configured ilk of a watched address
+#[derive(Debug, Clone, Default)]
+pub struct WatchTotal {
+    pub label: String,
+    pub value: U256,
+    pub loan: U256,
+}
+
+impl WatchTotal {
+    pub fn safety(&self) -> f64 {
+        let units = units::new();
+        match self.loan.is_zero() {
+            true => 0.0,
+            false => (units.BLN * self.value / self.loan).as_u128() as f64 / units.BLN_F64,
+        }
+    }
+}
+
+// totals over every urn in the panel
+#[derive(Debug, Clone, Default)]
+pub struct Portfolio {
+    pub value: U256,
+    pub loan: U256,
+    pub debt: U256,
+    // fee apr in percent, weighted by each urn's debt
+    pub fee_apr: f64,
+    // rico (wad) the debt grows by over the next day at the current fees
+    pub daily_interest: U256,
+    // index of the urn with a loan and the lowest safety
+    pub weakest: Option<usize>,
+}
+
+impl Portfolio {
+    pub fn new(urns: &[UrnData]) -> Self {
+        let units = units::new();
+        let mut portfolio = Portfolio::default();
+        let mut weighted_fee = 0.0;
+        for (i, urn) in urns.iter().enumerate() {
+            portfolio.value += urn.value;
+            portfolio.loan += urn.loan;
+            portfolio.debt += urn.debt;
+            let weaker = match portfolio.weakest {
+                Some(weakest) => urn.safety < urns[weakest].safety,
+                None => true,
+            };
+            if !urn.loan.is_zero() && weaker {
+                portfolio.weakest = Some(i);
+            }
+            // placeholder urns haven't been fetched yet and have no fee
+            if urn.fee.is_zero() {
+                continue;
+            }
+            weighted_fee += fee_apr(urn.fee) * urn.debt.as_u128() as f64;
+            portfolio.daily_interest +=
+                urn.debt * (rpow(urn.fee, U256::from(24 * 3600)) - units.RAY) / units.RAY;
+        }
+        if !portfolio.debt.is_zero() {
+            portfolio.fee_apr = weighted_fee / portfolio.debt.as_u128() as f64;
+        }
+        portfolio
+    }
+
+    pub fn safety(&self) -> f64 {
+        let units = units::new();
+        match self.loan.is_zero() {
+            true => 0.0,
+            false => (units.BLN * self.value / self.loan).as_u128() as f64 / units.BLN_F64,
+        }
+    }
+
+    // daily interest in usd, rico is priced at mar (ref) and ref at xau (usd)
+    pub fn daily_interest_usd(&self, mar: U256, xau: U256) -> f64 {
+        let units = units::new();
+        (self.daily_interest * mar / units.RAY * xau / units.RAY).as_u128() as f64 / units.WAD_F64
+    }
+}
+
+// one row of the ilk table
+#[derive(Debug, Clone)]
+pub struct IlkRow {
+    pub name: String,
+    // tink and inkd filled in, for :uninft tink counts positions and inkd is 0
+    pub ilk: Ilk,
+    // None for :uninft, which has a liqr and feed per token
+    pub liqr: Option<U256>,
+    pub feed: Option<U256>,
+    // everything the vat holds at feed prices before liqr, ref wad
+    pub tvl: U256,
+    // when a cached tvl was computed, None when it was read with the row
+    pub tvl_valued: Option<Instant>,
+}
+
+impl IlkRow {
+    pub fn decimals(&self) -> u32 {
+        self.ilk.inkd.unwrap_or_default().as_u32()
+    }
+
+    pub fn price(&self) -> Option<f64> {
+        self.feed.map(|feed| feed_price(feed, self.decimals()))
+    }
+
+    pub fn tvl_rico(&self, par: U256) -> f64 {
+        let units = units::new();
+        match par.is_zero() {
+            true => 0.0,
+            false => to_f64(self.tvl * units.RAY / par, 18),
+        }
+    }
+
+    pub fn tvl_usd(&self, xau: U256) -> f64 {
+        let units = units::new();
+        to_f64(self.tvl * xau / units.RAY, 18)
+    }
+}
+
+// ref per whole token from a feed value. Feeds are ref wads per base unit as rays, the
+// convention value_gem uses (feed * ink / RAY is a ref wad), so a token with `decimals`
+// is worth feed / RAY * 10^(decimals - 18) ref.
+pub fn feed_price(feed: U256, decimals: u32) -> f64 {
+    to_f64(feed, 27) * 10_f64.powi(decimals as i32 - 18)
+}
+
+// fixed point to float without the u128 limit of as_u128
+pub fn to_f64(x: U256, decimals: u32) -> f64 {
+    ethers::utils::format_units(x, decimals)
+        .unwrap()
+        .parse::<f64>()
+        .unwrap_or_default()
+}
+
+// simple fee apr in percent, the way the ilk view shows it
+pub fn fee_apr(fee: U256) -> f64 {
+    let units = units::new();
+    (((fee * units.WAD) / units.RAY).as_u128() as f64 / units.BLN_F64.powf(2.0) - 1_f64)
+        * units.BANKYEAR
+        * 100.0
+}
+
+// a deposited :uninft position with the metadata needed to show its amounts
+#[derive(Debug, Clone)]
+pub struct NftRow {
+    pub breakdown: UniNftBreakdown,
+    pub symbol0: String,
+    pub symbol1: String,
+    pub decimals0: u32,
+    pub decimals1: u32,
+}
+
+// one token the :uninft hook has a feed for
+#[derive(Debug, Clone)]
+pub struct UniTokenRow {
+    pub info: UniTokenInfo,
+    pub symbol: String,
+    pub decimals: u32,
+}
+
+// :uninft ilk detail, every supported token and the (token_id, token0, token1) of each deposited position
+#[derive(Debug, Clone, Default)]
+pub struct UniIlkDetail {
+    pub tokens: Vec<UniTokenRow>,
+    pub positions: Vec<(U256, Address, Address)>,
+    // reads that failed, the detail only has what could be read
+    pub errors: Vec<String>,
+}
+
+impl UniIlkDetail {
+    pub fn token(&self, token: Address) -> Option<&UniTokenRow> {
+        self.tokens.iter().find(|row| row.info.token == token)
+    }
+
+    // the valuer divides by max(liqr0, liqr1), so the token with the higher liqr drives it
+    pub fn driver(&self, token0: Address, token1: Address) -> Option<&UniTokenRow> {
+        match (self.token(token0), self.token(token1)) {
+            (Some(row0), Some(row1)) => match row1.info.liqr > row0.info.liqr {
+                true => Some(row1),
+                false => Some(row0),
+            },
+            (row0, row1) => row0.or(row1),
+        }
+    }
+}
+
+// Points for the range chart of one :uninft position. x is token0's price in token1,
+// y is value after liqr in ref; token1's feed is held while token0's moves.
+#[derive(Debug, Clone, Default)]
+pub struct RangeSeries {
+    pub label: String,
+    pub value: Vec<(f64, f64)>,
+    // the position's current amounts held outside the pool, the gap to value is the IL
+    pub hodl: Vec<(f64, f64)>,
+    // value the position has to keep for the urn to stay safe, other positions held
+    pub threshold: Vec<(f64, f64)>,
+    pub current: Vec<(f64, f64)>,
+    pub lower: Vec<(f64, f64)>,
+    pub upper: Vec<(f64, f64)>,
+    pub x_bounds: [f64; 2],
+    pub y_bounds: [f64; 2],
+}
+
+impl RangeSeries {
+    pub fn new(row: &NftRow, rows: &[NftRow], loan: U256) -> Self {
+        let units = units::new();
+        let breakdown = &row.breakdown;
+        let (t0_price, t1_price) = breakdown.prices;
+        if t0_price.is_zero() || t1_price.is_zero() || breakdown.liqr.is_zero() {
+            return Self::default();
+        }
+        let decimals = 10_f64.powi(row.decimals0 as i32 - row.decimals1 as i32);
+        let current = t0_price.as_u128() as f64 / t1_price.as_u128() as f64 * decimals;
+        let (lower, upper) = breakdown
+            .position
+            .price_range(row.decimals0, row.decimals1);
+        // full range positions would squash everything else, keep within 100x of the price
+        let x_bounds = [
+            f64::max(f64::min(lower, current), current / 100.0) / 1.5,
+            f64::min(f64::max(upper, current), current * 100.0) * 1.5,
+        ];
+        let wad = |x: U256| x.as_u128() as f64 / units.WAD_F64;
+        let held = (
+            breakdown.principal.0 + breakdown.fees.0,
+            breakdown.principal.1 + breakdown.fees.1,
+        );
+        let steps = 100;
+        let mut value = Vec::<(f64, f64)>::new();
+        let mut hodl = Vec::<(f64, f64)>::new();
+        for step in 0..=steps {
+            // geometric steps so narrow ranges near the low end still get points
+            let x = x_bounds[0] * (x_bounds[1] / x_bounds[0]).powf(step as f64 / steps as f64);
+            let scale = U256::from((x / current * units.BLN_F64) as u128);
+            let price = t0_price * scale / units.BLN;
+            if price.is_zero() {
+                continue;
+            }
+            value.push((
+                x,
+                wad(value_position(
+                    &breakdown.position,
+                    breakdown.fees,
+                    price,
+                    t1_price,
+                    breakdown.liqr,
+                )),
+            ));
+            hodl.push((x, wad((held.0 * price + held.1 * t1_price) / breakdown.liqr)));
+        }
+        let others = rows
+            .iter()
+            .filter(|other| other.breakdown.token_id != breakdown.token_id)
+            .fold(U256::zero(), |acc, other| acc + other.breakdown.value);
+        let threshold = match loan.is_zero() {
+            true => Vec::new(),
+            false => {
+                let y = wad(loan.saturating_sub(others));
+                vec![(x_bounds[0], y), (x_bounds[1], y)]
+            }
+        };
+        let ys = value
+            .iter()
+            .chain(hodl.iter())
+            .chain(threshold.iter())
+            .map(|(_, y)| *y);
+        let y_bounds = [
+            ys.clone().fold(f64::INFINITY, f64::min) * 0.9,
+            ys.fold(0.0, f64::max) * 1.1,
+        ];
+        let vertical = |x: f64| vec![(x, y_bounds[0]), (x, y_bounds[1])];
+        RangeSeries {
+            label: format!("#{} {}/{}", breakdown.token_id, row.symbol0, row.symbol1),
+            value,
+            hodl,
+            threshold,
+            current: vertical(current),
+            lower: vertical(lower),
+            upper: vertical(upper),
+            x_bounds,
+            y_bounds,
+        }
+    }
+}
+
+// vat-wide totals for the protocol dashboard
+#[derive(Debug, Clone, Default)]
+pub struct Protocol {
+    // wads
+    pub debt: U256,
+    pub ceil: U256,
+    pub joy: U256,
+    // rads
+    pub sin: U256,
+    pub rest: U256,
+    pub par: U256,
+    pub ilks: Vec<IlkUtilization>,
+}
+
+// an ilk's debt (tart * rack) against its line, both rads
+#[derive(Debug, Clone)]
+pub struct IlkUtilization {
+    pub ilk: String,
+    pub debt: U256,
+    pub line: U256,
+}
+
+// share of `limit` used, 0 for no limit and capped at 1
+pub fn utilization(used: U256, limit: U256) -> f64 {
+    let units = units::new();
+    match limit.is_zero() {
+        true => 0.0,
+        false => f64::min((used * units.BLN / limit).as_u128() as f64 / units.BLN_F64, 1.0),
+    }
+}
+
+// feeds within this many seconds of their ttl are flagged in the oracle view
+pub const EXPIRY_WARNING_SECS: i64 = 600;
+
+// what pulling a feed returned
+#[derive(Debug, Clone)]
+pub enum FeedRead {
+    Value { val: U256, ttl: U256 },
+    // the pull reverted with ErrTTL
+    ErrTtl,
+    Failed(String),
+    // the feed's src and tag couldn't be read, so there was nothing to pull
+    Unresolved(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedStatus {
+    Fresh,
+    Expiring,
+    Stale,
+    Failed,
+}
+
+// a feed the terminal reads, labelled with what it prices
+#[derive(Debug, Clone)]
+pub struct OracleFeed {
+    pub label: String,
+    pub src: Address,
+    pub tag: H256,
+    pub read: FeedRead,
+}
+
+impl OracleFeed {
+    // seconds until the ttl, negative once it has passed, None for feeds that never expire
+    pub fn expires_in(&self, now: i64) -> Option<i64> {
+        match &self.read {
+            FeedRead::Value { ttl, .. } if *ttl <= U256::from(i64::MAX as u64) => {
+                Some(ttl.as_u64() as i64 - now)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn status(&self, now: i64) -> FeedStatus {
+        match (&self.read, self.expires_in(now)) {
+            (FeedRead::ErrTtl, _) => FeedStatus::Stale,
+            (FeedRead::Failed(_) | FeedRead::Unresolved(_), _) => FeedStatus::Failed,
+            (_, Some(left)) if left < 0 => FeedStatus::Stale,
+            (_, Some(left)) if left < EXPIRY_WARNING_SECS => FeedStatus::Expiring,
+            _ => FeedStatus::Fresh,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ilk_row(feed: U256, decimals: u32, tink: U256) -> IlkRow {
+        let units = units::new();
+        let mut ilk = Ilk::from((
+            U256::zero(),
+            units.RAY,
+            U256::zero(),
+            U256::zero(),
+            units.RAY,
+            U256::zero(),
+            units.RAY,
+            Address::zero(),
+        ));
+        ilk.tink = Some(tink);
+        ilk.inkd = Some(U256::from(decimals));
+        IlkRow {
+            name: String::new(),
+            tvl: tink * feed / units.RAY,
+            ilk,
+            liqr: Some(units.RAY),
+            feed: Some(feed),
+            tvl_valued: None,
+        }
+    }
+
+    #[test]
+    fn price_of_18_decimal_gem() {
+        // weth at 2000 ref, 3 weth in the vat
+        let row = ilk_row(
+            U256::from(2000) * U256::exp10(27),
+            18,
+            U256::from(3) * U256::exp10(18),
+        );
+        assert_eq!(row.price(), Some(2000.0));
+        assert_eq!(to_f64(row.tvl, 18), 6000.0);
+    }
+
+    #[test]
+    fn price_of_6_decimal_gem() {
+        // usdc at 1 ref, 5 usdc in the vat
+        let row = ilk_row(U256::exp10(39), 6, U256::from(5_000_000));
+        assert_eq!(row.price(), Some(1.0));
+        assert_eq!(to_f64(row.tvl, 18), 5.0);
+    }
+
+    #[test]
+    fn frob_query_rejects_unknown_ilks() {
+        let (gems, known) = (GemCache::new(), vec!["weth".to_string(), ":uninft".to_string()]);
+        let query = FrobQuery::parse("weth 1.5 -2", &known, &gems).unwrap();
+        assert_eq!(query.dink, I256::from_raw(U256::from(15) * U256::exp10(17)));
+        assert_eq!(query.dart, -I256::from_raw(U256::from(2) * U256::exp10(18)));
+        assert!(FrobQuery::parse(":uninft 1 1", &known, &gems).unwrap().dink.is_zero());
+        assert!(FrobQuery::parse("wbtc 1 1", &known, &gems).is_err());
+        assert!(FrobQuery::parse("weth x 1", &known, &gems).is_err());
+        assert!(FrobQuery::parse("weth 1", &known, &gems).is_err());
+    }
+
+    fn position_preview() -> PositionPreview {
+        PositionPreview {
+            token_id: U256::one(),
+            token0: Address::zero(),
+            token1: Address::zero(),
+            decimals0: 6,
+            decimals1: 18,
+            liquidity: 1_000,
+            total: (U256::from(1_010_000), U256::from(2_000_000)),
+            fees: (U256::from(10_000), U256::zero()),
+        }
+    }
+
+    #[test]
+    fn liquidity_minimums_leave_out_fees_and_slippage() {
+        let preview = position_preview();
+        assert_eq!(preview.decrease(50), (U256::from(510_000), U256::from(1_000_000)));
+        assert_eq!(preview.decrease_min(50, 0.5), (U256::from(497_500), U256::from(995_000)));
+        assert_eq!(preview.increase_min(25, 1.0), (U256::from(247_500), U256::from(495_000)));
+        assert_eq!(preview.decrease_min(100, 0.0), preview.principal());
+    }
+
+    #[test]
+    fn slippage_is_clamped() {
+        assert_eq!(with_slippage(U256::from(10_000), 0.01), U256::from(9_999));
+        assert_eq!(with_slippage(U256::from(10_000), 150.0), U256::zero());
+        assert_eq!(with_slippage(U256::from(10_000), -1.0), U256::from(10_000));
+    }
+}